@@ -0,0 +1,760 @@
+use day_2::{build_game, get_games_from_file, get_score_for_game, HandShape, Result};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::env;
+use std::fmt;
+use std::str::FromStr;
+
+const HAND_SHAPES: [HandShape; 3] = [HandShape::Rock, HandShape::Paper, HandShape::Scissors];
+const RESULTS: [Result; 3] = [Result::Win, Result::Draw, Result::Lose];
+
+#[derive(Debug, Default, Copy, Clone)]
+struct Constraints {
+    max_wins: Option<usize>,
+    max_consecutive_wins: Option<usize>,
+    target_ratio: Option<Ratio>,
+}
+
+// Win, draw and lose weights, turned into exact counts for the number of rounds played. At least
+// one of them isn't zero, so there's always something to share the rounds out by
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct Ratio {
+    wins: usize,
+    draws: usize,
+    losses: usize,
+}
+
+impl Ratio {
+    fn new(wins: usize, draws: usize, losses: usize) -> Option<Ratio> {
+        (wins + draws + losses > 0).then_some(Ratio {
+            wins,
+            draws,
+            losses,
+        })
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum ParseRatioError {
+    WrongNumberOfWeights(String),
+    InvalidWeight(String),
+    AllZero(String),
+}
+
+impl fmt::Display for ParseRatioError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseRatioError::WrongNumberOfWeights(text) => {
+                write!(f, "{:?} isn't of the form win:draw:lose", text)
+            }
+            ParseRatioError::InvalidWeight(text) => write!(f, "{:?} isn't a valid weight", text),
+            ParseRatioError::AllZero(text) => {
+                write!(f, "{:?} needs at least one weight that isn't 0", text)
+            }
+        }
+    }
+}
+
+impl FromStr for Ratio {
+    type Err = ParseRatioError;
+
+    fn from_str(text: &str) -> std::result::Result<Ratio, ParseRatioError> {
+        let weights = text
+            .split(':')
+            .map(|weight| {
+                weight
+                    .trim()
+                    .parse()
+                    .map_err(|_| ParseRatioError::InvalidWeight(weight.to_string()))
+            })
+            .collect::<std::result::Result<Vec<usize>, ParseRatioError>>()?;
+
+        match weights[..] {
+            [wins, draws, losses] => Ratio::new(wins, draws, losses)
+                .ok_or_else(|| ParseRatioError::AllZero(text.to_string())),
+            _ => Err(ParseRatioError::WrongNumberOfWeights(text.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Plan {
+    moves: Vec<HandShape>,
+    results: Vec<Result>,
+    score: i128,
+}
+
+fn get_hand_shape_for_result(opponent: HandShape, result: Result) -> HandShape {
+    match (opponent, result) {
+        (_, Result::Draw) => opponent,
+        (HandShape::Rock, Result::Win) => HandShape::Paper,
+        (HandShape::Rock, Result::Lose) => HandShape::Scissors,
+        (HandShape::Paper, Result::Win) => HandShape::Scissors,
+        (HandShape::Paper, Result::Lose) => HandShape::Rock,
+        (HandShape::Scissors, Result::Win) => HandShape::Rock,
+        (HandShape::Scissors, Result::Lose) => HandShape::Paper,
+    }
+}
+
+fn get_score_for_round(opponent: HandShape, result: Result) -> i128 {
+    get_score_for_game(&build_game(
+        opponent,
+        get_hand_shape_for_result(opponent, result),
+    ))
+}
+
+fn get_target_counts(rounds: usize, ratio: Ratio) -> (usize, usize, usize) {
+    let weights = [ratio.wins, ratio.draws, ratio.losses];
+    let total_weight: usize = weights.iter().sum();
+
+    // Largest remainder rounding, so the counts always add up to the number of rounds
+    let mut counts = weights.map(|weight| weight * rounds / total_weight);
+    let mut remainders: Vec<(usize, usize)> = weights
+        .iter()
+        .enumerate()
+        .map(|(index, weight)| (weight * rounds % total_weight, index))
+        .collect();
+    remainders.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+
+    let assigned: usize = counts.iter().sum();
+    for (_, index) in remainders.iter().take(rounds - assigned) {
+        counts[*index] += 1;
+    }
+
+    (counts[0], counts[1], counts[2])
+}
+
+// Without a streak limit the order of the rounds doesn't matter, only how many of each result are
+// played against each hand shape. Once the counts against rock are picked, the rest are played
+// against paper or scissors, and paper takes the results it gains most from over scissors
+fn get_results_for_counts(
+    opponents: &[HandShape],
+    target_counts: (usize, usize, usize),
+) -> Option<Vec<Result>> {
+    let targets = [target_counts.0, target_counts.1, target_counts.2];
+    let shape_counts = HAND_SHAPES.map(|hand_shape| {
+        opponents
+            .iter()
+            .filter(|opponent| **opponent == hand_shape)
+            .count()
+    });
+    let points =
+        |shape: usize, result: usize| get_score_for_round(HAND_SHAPES[shape], RESULTS[result]);
+
+    let mut paper_order = [0, 1, 2];
+    paper_order.sort_by_key(|result| Reverse(points(1, *result) - points(2, *result)));
+
+    let mut best: Option<(i128, [[usize; 3]; 3])> = None;
+    for rock_wins in 0..=shape_counts[0].min(targets[0]) {
+        for rock_draws in 0..=(shape_counts[0] - rock_wins).min(targets[1]) {
+            let rock = [
+                rock_wins,
+                rock_draws,
+                shape_counts[0] - rock_wins - rock_draws,
+            ];
+            if rock[2] > targets[2] {
+                continue;
+            }
+            let left: [usize; 3] = std::array::from_fn(|result| targets[result] - rock[result]);
+
+            let mut paper = [0; 3];
+            let mut paper_left = shape_counts[1];
+            for result in paper_order {
+                paper[result] = paper_left.min(left[result]);
+                paper_left -= paper[result];
+            }
+            let scissors: [usize; 3] = std::array::from_fn(|result| left[result] - paper[result]);
+
+            let counts = [rock, paper, scissors];
+            let score: i128 = (0..3)
+                .flat_map(|shape| (0..3).map(move |result| (shape, result)))
+                .map(|(shape, result)| counts[shape][result] as i128 * points(shape, result))
+                .sum();
+            if best.is_none_or(|(best_score, _)| score > best_score) {
+                best = Some((score, counts));
+            }
+        }
+    }
+
+    // Hand out the results against each hand shape in the order the rounds come
+    let (_, mut counts) = best?;
+    let results = opponents
+        .iter()
+        .map(|opponent| {
+            let shape = HAND_SHAPES
+                .iter()
+                .position(|hand_shape| hand_shape == opponent)
+                .unwrap();
+            let result = (0..3).find(|result| counts[shape][*result] > 0).unwrap();
+            counts[shape][result] -= 1;
+            RESULTS[result]
+        })
+        .collect();
+
+    Some(results)
+}
+
+// What a round ends in, with only the parts the constraints need tracked, the rest stay at 0
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct State {
+    wins: usize,
+    draws: usize,
+    streak: usize,
+}
+
+// The best score so far for every state, None where it can't be reached. A round is worth at most
+// 9 points, so i32 is plenty and keeps the layers small
+type Layer = Vec<Option<i32>>;
+
+struct RoundByRound<'a> {
+    opponents: &'a [HandShape],
+    is_tracking_wins: bool,
+    is_tracking_draws: bool,
+    is_tracking_streak: bool,
+    max_wins: usize,
+    max_draws: usize,
+    max_losses: usize,
+    max_streak: usize,
+}
+
+impl RoundByRound<'_> {
+    fn new<'a>(
+        opponents: &'a [HandShape],
+        constraints: &Constraints,
+        target_counts: Option<(usize, usize, usize)>,
+    ) -> RoundByRound<'a> {
+        let rounds = opponents.len();
+        RoundByRound {
+            opponents,
+            is_tracking_wins: constraints.max_wins.is_some() || target_counts.is_some(),
+            is_tracking_draws: target_counts.is_some(),
+            is_tracking_streak: constraints.max_consecutive_wins.is_some(),
+            max_wins: match target_counts {
+                Some((wins, _, _)) => wins,
+                None => constraints.max_wins.unwrap_or(0).min(rounds),
+            },
+            max_draws: target_counts.map_or(0, |(_, draws, _)| draws),
+            max_losses: target_counts.map_or(rounds, |(_, _, losses)| losses),
+            max_streak: constraints.max_consecutive_wins.unwrap_or(0).min(rounds),
+        }
+    }
+
+    fn to_index(&self, state: State) -> usize {
+        (state.wins * (self.max_draws + 1) + state.draws) * (self.max_streak + 1) + state.streak
+    }
+
+    fn get_first_layer(&self) -> Layer {
+        let mut layer = vec![None; self.to_index(self.get_last_state()) + 1];
+        layer[0] = Some(0);
+        layer
+    }
+
+    fn get_last_state(&self) -> State {
+        State {
+            wins: self.max_wins,
+            draws: self.max_draws,
+            streak: self.max_streak,
+        }
+    }
+
+    fn get_points(&self, round: usize, result: Result) -> i32 {
+        get_score_for_round(self.opponents[round], result) as i32
+    }
+
+    // Where a result in the given round leads, None if that breaks a constraint
+    fn get_next_state(&self, round: usize, state: State, result: Result) -> Option<State> {
+        let next_state = match result {
+            Result::Win => State {
+                wins: state.wins + usize::from(self.is_tracking_wins),
+                streak: state.streak + usize::from(self.is_tracking_streak),
+                ..state
+            },
+            Result::Draw => State {
+                draws: state.draws + usize::from(self.is_tracking_draws),
+                streak: 0,
+                ..state
+            },
+            Result::Lose => State { streak: 0, ..state },
+        };
+        let losses = round + 1 - next_state.wins - next_state.draws;
+
+        (next_state.wins <= self.max_wins
+            && next_state.draws <= self.max_draws
+            && next_state.streak <= self.max_streak
+            && losses <= self.max_losses)
+            .then_some(next_state)
+    }
+
+    // Only the states that can be reached after the given number of rounds are looked at
+    fn get_states(&self, round: usize) -> impl Iterator<Item = State> + '_ {
+        (0..=self.max_wins.min(round)).flat_map(move |wins| {
+            let fewest_draws = (round - wins).saturating_sub(self.max_losses);
+            (fewest_draws..=self.max_draws.min(round - wins)).flat_map(move |draws| {
+                (0..=self.max_streak.min(round)).map(move |streak| State {
+                    wins,
+                    draws,
+                    streak,
+                })
+            })
+        })
+    }
+
+    // Fills in the next layer, which is passed in so the same one can be used again
+    fn play_round(&self, layer: &Layer, round: usize, next_layer: &mut Layer) {
+        next_layer.fill(None);
+        let points = RESULTS.map(|result| self.get_points(round, result));
+        for state in self.get_states(round) {
+            let Some(score) = layer[self.to_index(state)] else {
+                continue;
+            };
+            for (result, points) in RESULTS.into_iter().zip(points) {
+                let Some(next_state) = self.get_next_state(round, state, result) else {
+                    continue;
+                };
+                let next_score = score + points;
+                let best = &mut next_layer[self.to_index(next_state)];
+                if best.is_none_or(|best| next_score > best) {
+                    *best = Some(next_score);
+                }
+            }
+        }
+    }
+
+    // A state and result in the given round that lead to the given state with the given score.
+    // The state before differs by at most a win and a draw, with any streak
+    fn get_previous(
+        &self,
+        layer: &Layer,
+        round: usize,
+        state: State,
+        score: i32,
+    ) -> (State, Result) {
+        let candidates = (state.wins.saturating_sub(1)..=state.wins).flat_map(|wins| {
+            (state.draws.saturating_sub(1)..=state.draws).flat_map(move |draws| {
+                (0..=self.max_streak).map(move |streak| State {
+                    wins,
+                    draws,
+                    streak,
+                })
+            })
+        });
+
+        for previous_state in candidates {
+            let Some(previous_score) = layer[self.to_index(previous_state)] else {
+                continue;
+            };
+            for result in RESULTS {
+                if self.get_next_state(round, previous_state, result) == Some(state)
+                    && previous_score + self.get_points(round, result) == score
+                {
+                    return (previous_state, result);
+                }
+            }
+        }
+        panic!(
+            "Round {} should have been reached from an earlier state",
+            round + 1
+        );
+    }
+}
+
+// Plays the rounds in order, keeping the best score for every state the constraints care about.
+// Keeping every round's layer to walk back through would take rounds × states, so only every
+// block_size-th one is kept, and each block is played again from it on the way back
+fn get_results_by_round(
+    opponents: &[HandShape],
+    constraints: &Constraints,
+    target_counts: Option<(usize, usize, usize)>,
+) -> Option<Vec<Result>> {
+    let rounds = opponents.len();
+    let round_by_round = RoundByRound::new(opponents, constraints, target_counts);
+    let block_size = rounds.isqrt().max(1);
+
+    let mut checkpoints: Vec<Layer> = Vec::with_capacity(rounds.div_ceil(block_size));
+    let mut layer = round_by_round.get_first_layer();
+    let mut next_layer = layer.clone();
+    for round in 0..rounds {
+        if round % block_size == 0 {
+            checkpoints.push(layer.clone());
+        }
+        round_by_round.play_round(&layer, round, &mut next_layer);
+        std::mem::swap(&mut layer, &mut next_layer);
+    }
+
+    let (mut state, mut score) = round_by_round
+        .get_states(rounds)
+        .filter(|state| match target_counts {
+            Some((wins, draws, _)) => state.wins == wins && state.draws == draws,
+            None => true,
+        })
+        .filter_map(|state| layer[round_by_round.to_index(state)].map(|score| (state, score)))
+        .fold(
+            None,
+            |best: Option<(State, i32)>, (state, score)| match best {
+                Some((_, best_score)) if best_score >= score => best,
+                _ => Some((state, score)),
+            },
+        )?;
+
+    // The layers of a block are played into the same buffers for every block
+    let mut layers: Vec<Layer> = vec![layer; block_size.min(rounds)];
+    let mut results: Vec<Result> = vec![Result::Lose; rounds];
+    for (block, checkpoint) in checkpoints.into_iter().enumerate().rev() {
+        let start = block * block_size;
+        let end = (start + block_size).min(rounds);
+
+        layers[0] = checkpoint;
+        for round in start..end - 1 {
+            let (played, unplayed) = layers.split_at_mut(round - start + 1);
+            round_by_round.play_round(&played[round - start], round, &mut unplayed[0]);
+        }
+
+        for round in (start..end).rev() {
+            let (previous_state, result) =
+                round_by_round.get_previous(&layers[round - start], round, state, score);
+            score -= round_by_round.get_points(round, result);
+            state = previous_state;
+            results[round] = result;
+        }
+    }
+
+    Some(results)
+}
+
+fn find_best_plan(opponents: &[HandShape], constraints: &Constraints) -> Option<Plan> {
+    let target_counts = constraints
+        .target_ratio
+        .map(|ratio| get_target_counts(opponents.len(), ratio));
+
+    if let (Some((wins, _, _)), Some(max_wins)) = (target_counts, constraints.max_wins) {
+        if wins > max_wins {
+            return None;
+        }
+    }
+
+    let results = match (target_counts, constraints.max_consecutive_wins) {
+        (Some(target_counts), None) => get_results_for_counts(opponents, target_counts)?,
+        _ => get_results_by_round(opponents, constraints, target_counts)?,
+    };
+
+    let moves = opponents
+        .iter()
+        .zip(results.iter())
+        .map(|(opponent, result)| get_hand_shape_for_result(*opponent, *result))
+        .collect();
+    let score = opponents
+        .iter()
+        .zip(results.iter())
+        .map(|(opponent, result)| get_score_for_round(*opponent, *result))
+        .sum();
+
+    Some(Plan {
+        moves,
+        results,
+        score,
+    })
+}
+
+fn find_best_plan_for_file(file_path: &str, constraints: &Constraints) -> Option<Plan> {
+    let char_to_hand_shape = HashMap::from([
+        ("A", HandShape::Rock),
+        ("X", HandShape::Rock),
+        ("B", HandShape::Paper),
+        ("Y", HandShape::Paper),
+        ("C", HandShape::Scissors),
+        ("Z", HandShape::Scissors),
+    ]);
+
+    let opponents: Vec<HandShape> = get_games_from_file(file_path, char_to_hand_shape)
+        .iter()
+        .map(|game| game.opponent)
+        .collect();
+
+    find_best_plan(&opponents, constraints)
+}
+
+fn format_moves(moves: &[HandShape]) -> String {
+    moves
+        .iter()
+        .map(|hand_shape| match hand_shape {
+            HandShape::Rock => 'X',
+            HandShape::Paper => 'Y',
+            HandShape::Scissors => 'Z',
+        })
+        .collect()
+}
+
+fn print_plan(description: &str, plan: Option<Plan>) {
+    match plan {
+        Some(plan) => println!(
+            "{}: {} {}",
+            description,
+            plan.score,
+            format_moves(&plan.moves)
+        ),
+        None => println!("{}: no plan satisfies the constraints", description),
+    }
+}
+
+// "any" leaves the constraint out
+fn parse_constraint<T: FromStr>(text: &str) -> std::result::Result<Option<T>, T::Err> {
+    match text {
+        "any" => Ok(None),
+        _ => text.parse().map(Some),
+    }
+}
+
+fn print_scenarios() {
+    let scenarios = [
+        ("No constraints", Constraints::default()),
+        (
+            "Win at most 800 rounds",
+            Constraints {
+                max_wins: Some(800),
+                ..Constraints::default()
+            },
+        ),
+        (
+            "Never win more than 2 rounds in a row",
+            Constraints {
+                max_consecutive_wins: Some(2),
+                ..Constraints::default()
+            },
+        ),
+        (
+            "Win, draw and lose in a 1:1:1 ratio",
+            Constraints {
+                target_ratio: Ratio::new(1, 1, 1),
+                ..Constraints::default()
+            },
+        ),
+    ];
+
+    for (description, constraints) in scenarios {
+        print_plan(
+            description,
+            find_best_plan_for_file("./scores.txt", &constraints),
+        );
+    }
+}
+
+fn main() {
+    let arguments: Vec<String> = env::args().skip(1).collect();
+
+    match arguments.iter().map(String::as_str).collect::<Vec<&str>>()[..] {
+        // cargo run --release --bin optimiser -- <max wins|any> <max consecutive wins|any> <win:draw:lose|any> [file]
+        [max_wins, max_consecutive_wins, target_ratio, ref file_path @ ..] => {
+            let constraints = Constraints {
+                max_wins: parse_constraint(max_wins).expect("Max wins should be a number"),
+                max_consecutive_wins: parse_constraint(max_consecutive_wins)
+                    .expect("Max consecutive wins should be a number"),
+                target_ratio: parse_constraint(target_ratio)
+                    .unwrap_or_else(|error| panic!("{}", error)),
+            };
+            let file_path = match file_path {
+                [] => "./scores.txt",
+                [file_path] => file_path,
+                _ => panic!("Only one file can be given"),
+            };
+            print_plan(
+                "Best plan",
+                find_best_plan_for_file(file_path, &constraints),
+            );
+        }
+        _ => print_scenarios(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_returns_expected_scores_for_test_file() {
+        let unconstrained = find_best_plan_for_file("./test.txt", &Constraints::default());
+        assert_eq!(unconstrained.unwrap().score, 24);
+
+        let no_wins = find_best_plan_for_file(
+            "./test.txt",
+            &Constraints {
+                max_wins: Some(0),
+                ..Constraints::default()
+            },
+        );
+        assert_eq!(no_wins.unwrap().score, 15);
+
+        let no_winning_streaks = find_best_plan_for_file(
+            "./test.txt",
+            &Constraints {
+                max_consecutive_wins: Some(1),
+                ..Constraints::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(no_winning_streaks.score, 20);
+        assert_eq!(
+            no_winning_streaks.results,
+            [Result::Win, Result::Draw, Result::Win]
+        );
+
+        let even_ratio = find_best_plan_for_file(
+            "./test.txt",
+            &Constraints {
+                target_ratio: Ratio::new(1, 1, 1),
+                ..Constraints::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(even_ratio.score, 18);
+        assert_eq!(format_moves(&even_ratio.moves), "ZZZ");
+    }
+
+    fn get_longest_streak(results: &[Result]) -> usize {
+        results
+            .iter()
+            .fold((0, 0), |(longest, current), result| {
+                let current = if *result == Result::Win {
+                    current + 1
+                } else {
+                    0
+                };
+                (longest.max(current), current)
+            })
+            .0
+    }
+
+    fn count_results(results: &[Result]) -> (usize, usize, usize) {
+        let count = |wanted: Result| results.iter().filter(|result| **result == wanted).count();
+        (count(Result::Win), count(Result::Draw), count(Result::Lose))
+    }
+
+    #[test]
+    fn it_parses_ratios() {
+        assert_eq!("1:2:3".parse(), Ok(Ratio::new(1, 2, 3).unwrap()));
+        assert_eq!("0:0:1".parse(), Ok(Ratio::new(0, 0, 1).unwrap()));
+        assert_eq!(
+            "0:0:0".parse::<Ratio>(),
+            Err(ParseRatioError::AllZero("0:0:0".to_string()))
+        );
+        assert_eq!(
+            "1:1".parse::<Ratio>(),
+            Err(ParseRatioError::WrongNumberOfWeights("1:1".to_string()))
+        );
+        assert_eq!(
+            "1:x:1".parse::<Ratio>(),
+            Err(ParseRatioError::InvalidWeight("x".to_string()))
+        );
+    }
+
+    #[test]
+    fn it_respects_constraints_for_scores_file() {
+        let constraints = Constraints {
+            max_wins: Some(1000),
+            max_consecutive_wins: Some(3),
+            ..Constraints::default()
+        };
+        let plan = find_best_plan_for_file("./scores.txt", &constraints).unwrap();
+
+        let wins = plan.results.iter().filter(|result| **result == Result::Win);
+        assert!(wins.count() <= 1000);
+
+        assert!(get_longest_streak(&plan.results) <= 3);
+
+        let unconstrained = find_best_plan_for_file("./scores.txt", &Constraints::default());
+        assert!(plan.score <= unconstrained.unwrap().score);
+    }
+
+    #[test]
+    fn it_rejects_ratios_that_break_the_win_limit() {
+        let constraints = Constraints {
+            max_wins: Some(0),
+            target_ratio: Ratio::new(1, 1, 1),
+            ..Constraints::default()
+        };
+        assert_eq!(find_best_plan_for_file("./test.txt", &constraints), None);
+    }
+
+    #[test]
+    fn it_matches_a_ratio_on_the_scores_file() {
+        let constraints = Constraints {
+            target_ratio: Ratio::new(1, 1, 1),
+            ..Constraints::default()
+        };
+        let plan = find_best_plan_for_file("./scores.txt", &constraints).unwrap();
+
+        assert_eq!(
+            count_results(&plan.results),
+            get_target_counts(plan.results.len(), Ratio::new(1, 1, 1).unwrap())
+        );
+
+        let unconstrained = find_best_plan_for_file("./scores.txt", &Constraints::default());
+        assert!(plan.score <= unconstrained.unwrap().score);
+    }
+
+    #[test]
+    fn it_matches_a_ratio_without_streaks_on_the_scores_file() {
+        let ratio = Ratio::new(1, 0, 4).unwrap();
+        let constraints = Constraints {
+            max_consecutive_wins: Some(1),
+            target_ratio: Some(ratio),
+            ..Constraints::default()
+        };
+        let plan = find_best_plan_for_file("./scores.txt", &constraints).unwrap();
+
+        assert_eq!(
+            count_results(&plan.results),
+            get_target_counts(plan.results.len(), ratio)
+        );
+        assert_eq!(get_longest_streak(&plan.results), 1);
+
+        let any_streaks = Constraints {
+            max_consecutive_wins: None,
+            ..constraints
+        };
+        let plan_with_streaks = find_best_plan_for_file("./scores.txt", &any_streaks);
+        assert!(plan.score <= plan_with_streaks.unwrap().score);
+    }
+
+    // Every game of up to 6 rounds against every ratio with weights up to 2, enough rounds for the
+    // walk back to go through several blocks
+    fn get_games_and_ratios() -> impl Iterator<Item = (Vec<HandShape>, Ratio)> {
+        let games = (1..=6u32).flat_map(|rounds| {
+            (0..3usize.pow(rounds)).map(move |game| {
+                (0..rounds)
+                    .map(|round| HAND_SHAPES[game / 3usize.pow(round) % 3])
+                    .collect::<Vec<HandShape>>()
+            })
+        });
+        games.flat_map(|opponents| {
+            (0..27)
+                .filter_map(|weights| Ratio::new(weights / 9, weights / 3 % 3, weights % 3))
+                .map(move |ratio| (opponents.clone(), ratio))
+        })
+    }
+
+    #[test]
+    fn it_matches_a_ratio_by_counts_as_well_as_round_by_round() {
+        for (opponents, ratio) in get_games_and_ratios() {
+            let target_counts = get_target_counts(opponents.len(), ratio);
+
+            // A streak limit as long as the game changes nothing, but makes the rounds count
+            let by_round = Constraints {
+                max_consecutive_wins: Some(opponents.len()),
+                target_ratio: Some(ratio),
+                ..Constraints::default()
+            };
+            let score = |results: Vec<Result>| -> i128 {
+                opponents
+                    .iter()
+                    .zip(results)
+                    .map(|(opponent, result)| get_score_for_round(*opponent, result))
+                    .sum()
+            };
+
+            assert_eq!(
+                get_results_for_counts(&opponents, target_counts).map(score),
+                get_results_by_round(&opponents, &by_round, Some(target_counts)).map(score)
+            );
+        }
+    }
+}
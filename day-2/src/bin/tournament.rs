@@ -0,0 +1,317 @@
+use day_2::{build_game, get_games_from_file, get_result, get_score_for_game, HandShape, Result};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+trait Bot {
+    fn name(&self) -> String;
+
+    // Picks the next move, knowing every move the opponent has played so far
+    fn choose(&mut self, opponent_history: &[HandShape]) -> HandShape;
+}
+
+#[derive(Debug)]
+struct RoundLog {
+    round: usize,
+    opponent: HandShape,
+    player: HandShape,
+    result: Result,
+    score: i128,
+}
+
+#[derive(Debug)]
+struct Standing {
+    name: String,
+    score: i128,
+    wins: usize,
+    draws: usize,
+    losses: usize,
+    rounds: Vec<RoundLog>,
+}
+
+fn get_winning_hand_shape(opponent: HandShape) -> HandShape {
+    match opponent {
+        HandShape::Rock => HandShape::Paper,
+        HandShape::Paper => HandShape::Scissors,
+        HandShape::Scissors => HandShape::Rock,
+    }
+}
+
+fn get_most_frequent_hand_shape<'a>(
+    hand_shapes: impl Iterator<Item = &'a HandShape>,
+) -> Option<HandShape> {
+    let mut counts: HashMap<HandShape, usize> = HashMap::new();
+    for hand_shape in hand_shapes {
+        *counts.entry(*hand_shape).or_insert(0) += 1;
+    }
+
+    // Ties go to the earliest of rock, paper and scissors, so bots stay deterministic
+    counts
+        .into_iter()
+        .max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(&a.0)))
+        .map(|(hand_shape, _)| hand_shape)
+}
+
+struct StrategyGuide {
+    moves: Vec<HandShape>,
+}
+
+impl Bot for StrategyGuide {
+    fn name(&self) -> String {
+        "Strategy guide".to_string()
+    }
+
+    fn choose(&mut self, opponent_history: &[HandShape]) -> HandShape {
+        self.moves[opponent_history.len()]
+    }
+}
+
+struct BeatLastMove;
+
+impl Bot for BeatLastMove {
+    fn name(&self) -> String {
+        "Beat last move".to_string()
+    }
+
+    fn choose(&mut self, opponent_history: &[HandShape]) -> HandShape {
+        match opponent_history.last() {
+            Some(last_move) => get_winning_hand_shape(*last_move),
+            None => HandShape::Rock,
+        }
+    }
+}
+
+struct FrequencyCounter;
+
+impl Bot for FrequencyCounter {
+    fn name(&self) -> String {
+        "Frequency counter".to_string()
+    }
+
+    fn choose(&mut self, opponent_history: &[HandShape]) -> HandShape {
+        match get_most_frequent_hand_shape(opponent_history.iter()) {
+            Some(prediction) => get_winning_hand_shape(prediction),
+            None => HandShape::Rock,
+        }
+    }
+}
+
+struct MarkovPredictor {
+    order: usize,
+    transitions: HashMap<Vec<HandShape>, HashMap<HandShape, usize>>,
+}
+
+impl MarkovPredictor {
+    fn new(order: usize) -> MarkovPredictor {
+        MarkovPredictor {
+            order,
+            transitions: HashMap::new(),
+        }
+    }
+}
+
+impl Bot for MarkovPredictor {
+    fn name(&self) -> String {
+        format!("Markov predictor (order {})", self.order)
+    }
+
+    fn choose(&mut self, opponent_history: &[HandShape]) -> HandShape {
+        let length = opponent_history.len();
+
+        // Learn from the move the opponent has just made
+        if length > self.order {
+            let context = opponent_history[length - 1 - self.order..length - 1].to_vec();
+            *self
+                .transitions
+                .entry(context)
+                .or_default()
+                .entry(opponent_history[length - 1])
+                .or_insert(0) += 1;
+        }
+
+        let prediction = if length >= self.order {
+            self.transitions
+                .get(&opponent_history[length - self.order..])
+                .and_then(|next_moves| {
+                    let seen_moves = next_moves
+                        .iter()
+                        .flat_map(|(hand_shape, count)| std::iter::repeat_n(hand_shape, *count));
+                    get_most_frequent_hand_shape(seen_moves)
+                })
+        } else {
+            None
+        };
+
+        // Fall back to overall frequencies until the current context has been seen before
+        match prediction.or_else(|| get_most_frequent_hand_shape(opponent_history.iter())) {
+            Some(prediction) => get_winning_hand_shape(prediction),
+            None => HandShape::Rock,
+        }
+    }
+}
+
+fn play_tournament(opponents: &[HandShape], bots: &mut [Box<dyn Bot>]) -> Vec<Standing> {
+    let mut standings: Vec<Standing> = bots
+        .iter_mut()
+        .map(|bot| {
+            let mut standing = Standing {
+                name: bot.name(),
+                score: 0,
+                wins: 0,
+                draws: 0,
+                losses: 0,
+                rounds: Vec::with_capacity(opponents.len()),
+            };
+
+            for (round, opponent) in opponents.iter().enumerate() {
+                let player = bot.choose(&opponents[..round]);
+                let game = build_game(*opponent, player);
+                let result = get_result(&game);
+                let score = get_score_for_game(&game);
+
+                match result {
+                    Result::Win => standing.wins += 1,
+                    Result::Draw => standing.draws += 1,
+                    Result::Lose => standing.losses += 1,
+                }
+                standing.score += score;
+                standing.rounds.push(RoundLog {
+                    round: round + 1,
+                    opponent: *opponent,
+                    player,
+                    result,
+                    score,
+                });
+            }
+
+            standing
+        })
+        .collect();
+
+    standings.sort_by_key(|standing| Reverse(standing.score));
+
+    standings
+}
+
+fn play_tournament_for_file(file_path: &str) -> Vec<Standing> {
+    let char_to_hand_shape = HashMap::from([
+        ("A", HandShape::Rock),
+        ("X", HandShape::Rock),
+        ("B", HandShape::Paper),
+        ("Y", HandShape::Paper),
+        ("C", HandShape::Scissors),
+        ("Z", HandShape::Scissors),
+    ]);
+
+    let games = get_games_from_file(file_path, char_to_hand_shape);
+    let opponents: Vec<HandShape> = games.iter().map(|game| game.opponent).collect();
+
+    let mut bots: Vec<Box<dyn Bot>> = vec![
+        Box::new(StrategyGuide {
+            moves: games.iter().map(|game| game.player).collect(),
+        }),
+        Box::new(BeatLastMove),
+        Box::new(FrequencyCounter),
+        Box::new(MarkovPredictor::new(1)),
+        Box::new(MarkovPredictor::new(2)),
+        Box::new(MarkovPredictor::new(3)),
+    ];
+
+    play_tournament(&opponents, &mut bots)
+}
+
+fn print_leaderboard(standings: &[Standing]) {
+    for (position, standing) in standings.iter().enumerate() {
+        println!(
+            "{}. {}: {} ({} wins, {} draws, {} losses)",
+            position + 1,
+            standing.name,
+            standing.score,
+            standing.wins,
+            standing.draws,
+            standing.losses
+        );
+    }
+}
+
+fn print_round_logs(standings: &[Standing]) {
+    for standing in standings {
+        println!("{}", standing.name);
+        for round in &standing.rounds {
+            println!(
+                "  Round {}: {:?} vs {:?} -> {:?} ({})",
+                round.round, round.player, round.opponent, round.result, round.score
+            );
+        }
+    }
+}
+
+fn main() {
+    let test_standings = play_tournament_for_file("./test.txt");
+    print_round_logs(&test_standings);
+    print_leaderboard(&test_standings);
+
+    println!();
+
+    let standings = play_tournament_for_file("./scores.txt");
+    print_leaderboard(&standings);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_score_for_bot(standings: &[Standing], name: &str) -> i128 {
+        standings
+            .iter()
+            .find(|standing| standing.name == name)
+            .unwrap()
+            .score
+    }
+
+    #[test]
+    fn it_returns_expected_scores_for_test_file() {
+        let standings = play_tournament_for_file("./test.txt");
+        assert_eq!(get_score_for_bot(&standings, "Strategy guide"), 15);
+        assert_eq!(get_score_for_bot(&standings, "Beat last move"), 15);
+        assert_eq!(get_score_for_bot(&standings, "Frequency counter"), 11);
+        assert_eq!(
+            get_score_for_bot(&standings, "Markov predictor (order 1)"),
+            11
+        );
+        assert_eq!(standings[0].rounds.len(), 3);
+    }
+
+    #[test]
+    fn it_returns_expected_leaderboard_for_scores_file() {
+        let standings = play_tournament_for_file("./scores.txt");
+        assert_eq!(get_score_for_bot(&standings, "Strategy guide"), 12855);
+        assert!(standings
+            .windows(2)
+            .all(|pair| pair[0].score >= pair[1].score));
+    }
+
+    #[test]
+    fn it_plays_custom_bots() {
+        struct AlwaysPaper;
+
+        impl Bot for AlwaysPaper {
+            fn name(&self) -> String {
+                "Always paper".to_string()
+            }
+
+            fn choose(&mut self, _opponent_history: &[HandShape]) -> HandShape {
+                HandShape::Paper
+            }
+        }
+
+        let opponents = [HandShape::Rock, HandShape::Paper, HandShape::Scissors];
+        let mut bots: Vec<Box<dyn Bot>> = vec![Box::new(AlwaysPaper)];
+        let standings = play_tournament(&opponents, &mut bots);
+
+        assert_eq!(standings[0].score, 8 + 5 + 2);
+        assert_eq!(
+            (standings[0].wins, standings[0].draws, standings[0].losses),
+            (1, 1, 1)
+        );
+    }
+}
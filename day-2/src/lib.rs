@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+use std::fs;
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Copy, Clone)]
+pub enum HandShape {
+    Rock,
+    Paper,
+    Scissors,
+}
+
+#[derive(Debug, Eq, Hash, PartialEq)]
+pub struct Game {
+    pub opponent: HandShape,
+    pub player: HandShape,
+}
+
+pub fn build_game(opponent: HandShape, player: HandShape) -> Game {
+    Game { opponent, player }
+}
+
+#[derive(PartialEq, Eq, Hash, Debug, Copy, Clone)]
+pub enum Result {
+    Win,
+    Draw,
+    Lose,
+}
+
+pub fn get_games_from_file(
+    file_path: &str,
+    char_to_hand_shape: HashMap<&str, HandShape>,
+) -> Vec<Game> {
+    let file_contents =
+        fs::read_to_string(file_path).expect("Should have been able to read the file");
+
+    let file_contents_by_line = file_contents.lines();
+
+    let mut games: Vec<Game> = Vec::new();
+
+    for line in file_contents_by_line {
+        let choices: Vec<&str> = line.split(" ").collect();
+
+        let game = build_game(
+            char_to_hand_shape[choices[0]],
+            char_to_hand_shape[choices[1]],
+        );
+
+        games.push(game);
+    }
+
+    games
+}
+
+pub fn get_result(game: &Game) -> Result {
+    match (game.opponent, game.player) {
+        (HandShape::Rock, HandShape::Paper)
+        | (HandShape::Paper, HandShape::Scissors)
+        | (HandShape::Scissors, HandShape::Rock) => Result::Win,
+        (opponent, player) if opponent == player => Result::Draw,
+        _ => Result::Lose,
+    }
+}
+
+pub fn get_score_for_game(game: &Game) -> i128 {
+    let points_from_game = match get_result(game) {
+        Result::Win => 6,
+        Result::Draw => 3,
+        Result::Lose => 0,
+    };
+
+    let points_from_hand_shape = match game.player {
+        HandShape::Rock => 1,
+        HandShape::Paper => 2,
+        HandShape::Scissors => 3,
+    };
+
+    points_from_game + points_from_hand_shape
+}
@@ -0,0 +1,256 @@
+use crate::item_set::ItemSet;
+use crate::packing::get_common_items;
+use std::fmt;
+
+const GROUP_SIZE: usize = 3;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Anomaly {
+    OddLength {
+        line_number: usize,
+        length: usize,
+    },
+    InvalidItems {
+        line_number: usize,
+        items: Vec<char>,
+    },
+    NoSharedItem {
+        line_number: usize,
+    },
+    MultipleSharedItems {
+        line_number: usize,
+        items: ItemSet,
+    },
+    NoBadge {
+        first_line_number: usize,
+    },
+    MultipleBadges {
+        first_line_number: usize,
+        items: ItemSet,
+    },
+    IncompleteGroup {
+        first_line_number: usize,
+        rucksacks: usize,
+    },
+}
+
+impl fmt::Display for Anomaly {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Anomaly::OddLength {
+                line_number,
+                length,
+            } => write!(f, "Line {}: odd number of items ({})", line_number, length),
+            Anomaly::InvalidItems { line_number, items } => write!(
+                f,
+                "Line {}: invalid items {:?}",
+                line_number,
+                items.iter().collect::<String>()
+            ),
+            Anomaly::NoSharedItem { line_number } => {
+                write!(f, "Line {}: no item in both compartments", line_number)
+            }
+            Anomaly::MultipleSharedItems { line_number, items } => write!(
+                f,
+                "Line {}: {} items in both compartments ({})",
+                line_number,
+                items.len(),
+                items
+            ),
+            Anomaly::NoBadge { first_line_number } => write!(
+                f,
+                "Lines {}-{}: no badge candidate",
+                first_line_number,
+                first_line_number + GROUP_SIZE - 1
+            ),
+            Anomaly::MultipleBadges {
+                first_line_number,
+                items,
+            } => write!(
+                f,
+                "Lines {}-{}: {} badge candidates ({})",
+                first_line_number,
+                first_line_number + GROUP_SIZE - 1,
+                items.len(),
+                items
+            ),
+            Anomaly::IncompleteGroup {
+                first_line_number,
+                rucksacks,
+            } => write!(
+                f,
+                "Line {}: last group only has {} of {} rucksacks",
+                first_line_number, rucksacks, GROUP_SIZE
+            ),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct AuditReport {
+    pub anomalies: Vec<Anomaly>,
+    pub sum_of_priorities: u64,
+    pub sum_of_priorities_by_group: u64,
+}
+
+impl AuditReport {
+    pub fn exit_code(&self) -> i32 {
+        if self.anomalies.is_empty() {
+            0
+        } else {
+            1
+        }
+    }
+}
+
+// Invalid items are reported and left out, so the rest of the rucksack can still be checked
+fn get_valid_items(items: &[char], invalid_items: &mut Vec<char>) -> ItemSet {
+    ItemSet::from_chars(items.iter().copied()).unwrap_or_else(|error| {
+        invalid_items.extend(error.invalid_items);
+        error.valid_items
+    })
+}
+
+fn audit_rucksack(line_number: usize, line: &str, anomalies: &mut Vec<Anomaly>) -> (ItemSet, u64) {
+    let items: Vec<char> = line.chars().collect();
+
+    if !items.len().is_multiple_of(2) {
+        anomalies.push(Anomaly::OddLength {
+            line_number,
+            length: items.len(),
+        });
+    }
+
+    let (first_compartment, second_compartment) = items.split_at(items.len() / 2);
+    let mut invalid_items: Vec<char> = Vec::new();
+    let first_items = get_valid_items(first_compartment, &mut invalid_items);
+    let second_items = get_valid_items(second_compartment, &mut invalid_items);
+
+    if !invalid_items.is_empty() {
+        anomalies.push(Anomaly::InvalidItems {
+            line_number,
+            items: invalid_items,
+        });
+    }
+
+    let shared_items = first_items & second_items;
+    match shared_items.len() {
+        0 => anomalies.push(Anomaly::NoSharedItem { line_number }),
+        1 => (),
+        _ => anomalies.push(Anomaly::MultipleSharedItems {
+            line_number,
+            items: shared_items,
+        }),
+    }
+
+    (
+        first_items | second_items,
+        shared_items.get_priority() as u64,
+    )
+}
+
+pub fn audit_rucksacks(file_contents: &str) -> AuditReport {
+    let mut anomalies: Vec<Anomaly> = Vec::new();
+    let mut sum_of_priorities: u64 = 0;
+    let mut sum_of_priorities_by_group: u64 = 0;
+
+    let lines: Vec<&str> = file_contents.lines().collect();
+
+    for (group_index, group) in lines.chunks(GROUP_SIZE).enumerate() {
+        let first_line_number = group_index * GROUP_SIZE + 1;
+
+        let rucksacks: Vec<ItemSet> = group
+            .iter()
+            .enumerate()
+            .map(|(index, line)| {
+                let (rucksack, priority) =
+                    audit_rucksack(first_line_number + index, line, &mut anomalies);
+                sum_of_priorities += priority;
+                rucksack
+            })
+            .collect();
+
+        if rucksacks.len() < GROUP_SIZE {
+            anomalies.push(Anomaly::IncompleteGroup {
+                first_line_number,
+                rucksacks: rucksacks.len(),
+            });
+            continue;
+        }
+
+        let badges = get_common_items(&rucksacks);
+        match badges.len() {
+            0 => anomalies.push(Anomaly::NoBadge { first_line_number }),
+            1 => (),
+            _ => anomalies.push(Anomaly::MultipleBadges {
+                first_line_number,
+                items: badges,
+            }),
+        }
+        sum_of_priorities_by_group += badges.get_priority() as u64;
+    }
+
+    AuditReport {
+        anomalies,
+        sum_of_priorities,
+        sum_of_priorities_by_group,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn it_reports_no_anomalies_for_input_file() {
+        let file_contents =
+            fs::read_to_string("./input.txt").expect("Should have been able to read the file");
+        let report = audit_rucksacks(&file_contents);
+
+        assert_eq!(report.anomalies, []);
+        assert_eq!(report.exit_code(), 0);
+        assert_eq!(report.sum_of_priorities, 7811);
+        assert_eq!(report.sum_of_priorities_by_group, 2639);
+    }
+
+    #[test]
+    fn it_reports_every_anomaly_for_audit_file() {
+        let file_contents =
+            fs::read_to_string("./audit.txt").expect("Should have been able to read the file");
+        let report = audit_rucksacks(&file_contents);
+
+        assert_eq!(
+            report.anomalies,
+            [
+                Anomaly::OddLength {
+                    line_number: 2,
+                    length: 5
+                },
+                Anomaly::NoSharedItem { line_number: 3 },
+                Anomaly::MultipleSharedItems {
+                    line_number: 4,
+                    items: ItemSet::from_items("abA").unwrap()
+                },
+                Anomaly::InvalidItems {
+                    line_number: 5,
+                    items: vec!['1', '2']
+                },
+                Anomaly::MultipleBadges {
+                    first_line_number: 4,
+                    items: ItemSet::from_items("ab").unwrap()
+                },
+                Anomaly::NoBadge {
+                    first_line_number: 7
+                },
+                Anomaly::IncompleteGroup {
+                    first_line_number: 10,
+                    rucksacks: 1
+                },
+            ]
+        );
+        assert_eq!(report.exit_code(), 1);
+        assert_eq!(report.sum_of_priorities, 109);
+        assert_eq!(report.sum_of_priorities_by_group, 6);
+    }
+}
@@ -0,0 +1,241 @@
+use crate::item_set::ItemSet;
+use itertools::Itertools;
+use std::time::Instant;
+
+const NUMBER_OF_GROUPS: usize = 100_000;
+const COMPARTMENT_SIZE: usize = 16;
+
+const ASCII_LOWER: [char; 26] = [
+    'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r', 's',
+    't', 'u', 'v', 'w', 'x', 'y', 'z',
+];
+
+struct Random(u64);
+
+impl Random {
+    fn next(&mut self) -> u64 {
+        // xorshift64, so generated inputs are the same on every run
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn pick<T: Copy>(&mut self, items: &[T]) -> T {
+        items[self.next() as usize % items.len()]
+    }
+
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        for index in (1..items.len()).rev() {
+            items.swap(index, self.next() as usize % (index + 1));
+        }
+    }
+}
+
+// Every rucksack has exactly one item in both compartments and every group of three
+// shares exactly one badge, so both engines are guaranteed to agree
+fn generate_rucksacks(random: &mut Random, number_of_groups: usize) -> Vec<String> {
+    let all_items: Vec<char> = ('a'..='z').chain('A'..='Z').collect();
+    let mut rucksacks: Vec<String> = Vec::with_capacity(number_of_groups * 3);
+
+    for _ in 0..number_of_groups {
+        let mut items = all_items.clone();
+        random.shuffle(&mut items);
+
+        let badge = items[0];
+        let pools: Vec<&[char]> = items[1..].chunks(17).collect();
+
+        for index in 0..3 {
+            let first_pool = pools[index];
+            let second_pool = pools[(index + 1) % 3];
+            let shared_item = random.pick(first_pool);
+
+            let mut first_compartment = vec![badge, shared_item];
+            first_compartment.extend((2..COMPARTMENT_SIZE).map(|_| random.pick(first_pool)));
+            random.shuffle(&mut first_compartment);
+
+            let mut second_compartment = vec![shared_item];
+            second_compartment.extend((1..COMPARTMENT_SIZE).map(|_| random.pick(second_pool)));
+            random.shuffle(&mut second_compartment);
+
+            rucksacks.push(
+                first_compartment
+                    .iter()
+                    .chain(&second_compartment)
+                    .collect(),
+            );
+        }
+    }
+
+    rucksacks
+}
+
+// The character scans that item sets replaced, kept here to compare against
+fn get_shared_letter_from_codes(codes: &(String, String)) -> char {
+    let mut shared_letter: Option<char> = None;
+
+    for letter_in_code_one in codes.0.chars() {
+        if codes.1.contains(letter_in_code_one) {
+            shared_letter = Option::from(letter_in_code_one);
+        }
+
+        if let Some(_char) = shared_letter {
+            break;
+        }
+    }
+
+    shared_letter.unwrap()
+}
+
+fn get_common_letter_in_string(strings: &[&str]) -> char {
+    let first_code = strings[0];
+    let second_code = strings[1];
+    let third_code = strings[2];
+
+    for letter in first_code.chars() {
+        if second_code.contains(letter) && third_code.contains(letter) {
+            return letter;
+        }
+    }
+
+    panic!("No common letters found!");
+}
+
+fn to_chunks(string: &str, chunk_size: usize) -> (String, String) {
+    let mut sections: (String, String) = ("".to_string(), "".to_string());
+
+    for (index, chunk) in (&string.chars().chunks(chunk_size)).into_iter().enumerate() {
+        let string_from_chunk = String::from_iter(chunk);
+        if index % 2 == 0 {
+            sections.0 = string_from_chunk
+        } else {
+            sections.1 = string_from_chunk
+        }
+    }
+
+    sections
+}
+
+fn get_priority_for_char(char: &char) -> i16 {
+    let mut base_priority: i16 = 0;
+
+    if char.is_uppercase() {
+        base_priority = 26;
+    }
+
+    let letter_to_find: String = char.to_lowercase().to_string();
+
+    let index = ASCII_LOWER
+        .iter()
+        .position(|letter| letter.to_string() == letter_to_find)
+        .unwrap() as i16;
+
+    index + 1 + base_priority
+}
+
+fn time<T>(description: &str, run: impl Fn() -> T) -> T {
+    let start = Instant::now();
+    let result = run();
+    println!("{}: {:?}", description, start.elapsed());
+    result
+}
+
+fn sum_priorities_with_character_scans(rucksacks: &[String]) -> u64 {
+    rucksacks
+        .iter()
+        .map(|rucksack| {
+            let codes = to_chunks(rucksack, rucksack.len() / 2);
+            get_priority_for_char(&get_shared_letter_from_codes(&codes)) as u64
+        })
+        .sum()
+}
+
+fn sum_priorities_with_item_sets(rucksacks: &[String]) -> u64 {
+    rucksacks
+        .iter()
+        .map(|rucksack| {
+            let (first, second) = rucksack.split_at(rucksack.len() / 2);
+            (ItemSet::from_items(first).unwrap() & ItemSet::from_items(second).unwrap())
+                .get_priority() as u64
+        })
+        .sum()
+}
+
+fn sum_badge_priorities_with_character_scans(rucksacks: &[String]) -> u64 {
+    rucksacks
+        .chunks(3)
+        .map(|group| {
+            let group: Vec<&str> = group.iter().map(|rucksack| rucksack.as_str()).collect();
+            get_priority_for_char(&get_common_letter_in_string(&group)) as u64
+        })
+        .sum()
+}
+
+fn sum_badge_priorities_with_item_sets(rucksacks: &[String]) -> u64 {
+    rucksacks
+        .chunks(3)
+        .map(|group| {
+            group
+                .iter()
+                .map(|rucksack| ItemSet::from_items(rucksack).unwrap())
+                .reduce(|common_items, items| common_items & items)
+                .unwrap()
+                .get_priority() as u64
+        })
+        .sum()
+}
+
+pub fn run_benchmarks() {
+    let rucksacks = generate_rucksacks(&mut Random(0x2022_1203), NUMBER_OF_GROUPS);
+    println!("Generated {} rucksacks", rucksacks.len());
+
+    let character_scans = time("Part 1 with character scans", || {
+        sum_priorities_with_character_scans(&rucksacks)
+    });
+    let item_sets = time("Part 1 with item sets", || {
+        sum_priorities_with_item_sets(&rucksacks)
+    });
+    assert_eq!(character_scans, item_sets);
+    println!("Part 1 answers match: {}", item_sets);
+
+    let character_scans = time("Part 2 with character scans", || {
+        sum_badge_priorities_with_character_scans(&rucksacks)
+    });
+    let item_sets = time("Part 2 with item sets", || {
+        sum_badge_priorities_with_item_sets(&rucksacks)
+    });
+    assert_eq!(character_scans, item_sets);
+    println!("Part 2 answers match: {}", item_sets);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn it_returns_same_results_for_input_file() {
+        let file_contents =
+            fs::read_to_string("./input.txt").expect("Should have been able to read the file");
+        let rucksacks: Vec<String> = file_contents.lines().map(String::from).collect();
+
+        assert_eq!(sum_priorities_with_character_scans(&rucksacks), 7811);
+        assert_eq!(sum_priorities_with_item_sets(&rucksacks), 7811);
+        assert_eq!(sum_badge_priorities_with_character_scans(&rucksacks), 2639);
+        assert_eq!(sum_badge_priorities_with_item_sets(&rucksacks), 2639);
+    }
+
+    #[test]
+    fn it_returns_same_results_for_generated_rucksacks() {
+        let rucksacks = generate_rucksacks(&mut Random(42), 1_000);
+
+        assert_eq!(
+            sum_priorities_with_character_scans(&rucksacks),
+            sum_priorities_with_item_sets(&rucksacks)
+        );
+        assert_eq!(
+            sum_badge_priorities_with_character_scans(&rucksacks),
+            sum_badge_priorities_with_item_sets(&rucksacks)
+        );
+    }
+}
@@ -0,0 +1,198 @@
+use crate::item_set::ItemSet;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct BadgeGroup {
+    pub rucksacks: [usize; 3],
+    pub badge: ItemSet,
+}
+
+// An exact cover search: every rucksack has to end up in exactly one of the candidate triples
+struct Search {
+    triples: Vec<BadgeGroup>,
+    triples_for_rucksack: Vec<Vec<usize>>,
+    is_covered: Vec<bool>,
+    // How many members of each triple are already in a chosen group, a triple is only usable at 0
+    blocked_members: Vec<usize>,
+    usable_triples: Vec<usize>,
+    chosen: Vec<usize>,
+}
+
+impl Search {
+    fn new(rucksacks: &[ItemSet]) -> Search {
+        let mut triples: Vec<BadgeGroup> = Vec::new();
+        let mut triples_for_rucksack: Vec<Vec<usize>> = vec![Vec::new(); rucksacks.len()];
+
+        for first in 0..rucksacks.len() {
+            for second in first + 1..rucksacks.len() {
+                let common_items = rucksacks[first] & rucksacks[second];
+                if common_items.len() == 0 {
+                    continue;
+                }
+
+                for (third, rucksack) in rucksacks.iter().enumerate().skip(second + 1) {
+                    let badge = common_items & *rucksack;
+                    if badge.len() != 1 {
+                        continue;
+                    }
+
+                    for rucksack in [first, second, third] {
+                        triples_for_rucksack[rucksack].push(triples.len());
+                    }
+                    triples.push(BadgeGroup {
+                        rucksacks: [first, second, third],
+                        badge,
+                    });
+                }
+            }
+        }
+
+        let usable_triples = triples_for_rucksack.iter().map(Vec::len).collect();
+
+        Search {
+            blocked_members: vec![0; triples.len()],
+            triples,
+            triples_for_rucksack,
+            is_covered: vec![false; rucksacks.len()],
+            usable_triples,
+            chosen: Vec::new(),
+        }
+    }
+
+    fn choose(&mut self, triple: usize) {
+        for rucksack in self.triples[triple].rucksacks {
+            self.is_covered[rucksack] = true;
+            for &other_triple in &self.triples_for_rucksack[rucksack] {
+                self.blocked_members[other_triple] += 1;
+                if self.blocked_members[other_triple] == 1 {
+                    for member in self.triples[other_triple].rucksacks {
+                        self.usable_triples[member] -= 1;
+                    }
+                }
+            }
+        }
+        self.chosen.push(triple);
+    }
+
+    fn unchoose(&mut self, triple: usize) {
+        self.chosen.pop();
+        for rucksack in self.triples[triple].rucksacks.into_iter().rev() {
+            for &other_triple in self.triples_for_rucksack[rucksack].iter().rev() {
+                if self.blocked_members[other_triple] == 1 {
+                    for member in self.triples[other_triple].rucksacks {
+                        self.usable_triples[member] += 1;
+                    }
+                }
+                self.blocked_members[other_triple] -= 1;
+            }
+            self.is_covered[rucksack] = false;
+        }
+    }
+
+    fn solve(&mut self) -> bool {
+        // Always branch on the rucksack with the fewest options left, failing as soon as one has none
+        let most_constrained = (0..self.is_covered.len())
+            .filter(|rucksack| !self.is_covered[*rucksack])
+            .min_by_key(|rucksack| self.usable_triples[*rucksack]);
+
+        let Some(rucksack) = most_constrained else {
+            return true;
+        };
+
+        let candidates: Vec<usize> = self.triples_for_rucksack[rucksack]
+            .iter()
+            .copied()
+            .filter(|triple| self.blocked_members[*triple] == 0)
+            .collect();
+
+        for triple in candidates {
+            self.choose(triple);
+            if self.solve() {
+                return true;
+            }
+            self.unchoose(triple);
+        }
+
+        false
+    }
+}
+
+// Returns None when no partition into badge groups exists, which the exhaustive search proves
+pub fn find_badge_groups(rucksacks: &[ItemSet]) -> Option<Vec<BadgeGroup>> {
+    if !rucksacks.len().is_multiple_of(3) {
+        return None;
+    }
+
+    let mut search = Search::new(rucksacks);
+    if !search.solve() {
+        return None;
+    }
+
+    let mut groups: Vec<BadgeGroup> = search
+        .chosen
+        .iter()
+        .map(|triple| search.triples[*triple])
+        .collect();
+    groups.sort_by_key(|group| group.rucksacks[0]);
+
+    Some(groups)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn get_rucksacks_from_file(file_path: &str) -> Vec<ItemSet> {
+        let file_contents =
+            fs::read_to_string(file_path).expect("Should have been able to read the file");
+        file_contents
+            .lines()
+            .map(|line| ItemSet::from_items(line).unwrap())
+            .collect()
+    }
+
+    fn assert_is_partition(rucksacks: &[ItemSet], groups: &[BadgeGroup]) {
+        let mut seen = vec![false; rucksacks.len()];
+        for group in groups {
+            let [first, second, third] = group.rucksacks;
+            assert_eq!(
+                rucksacks[first] & rucksacks[second] & rucksacks[third],
+                group.badge
+            );
+            assert_eq!(group.badge.len(), 1);
+            for rucksack in group.rucksacks {
+                assert!(!seen[rucksack]);
+                seen[rucksack] = true;
+            }
+        }
+        assert!(seen.iter().all(|is_seen| *is_seen));
+    }
+
+    #[test]
+    fn it_finds_badge_groups_for_shuffled_test_file() {
+        let rucksacks = get_rucksacks_from_file("./test-shuffled.txt");
+        let groups = find_badge_groups(&rucksacks).unwrap();
+
+        assert_is_partition(&rucksacks, &groups);
+        assert_eq!(groups.len(), 2);
+    }
+
+    #[test]
+    fn it_finds_badge_groups_for_reversed_input_file() {
+        let mut rucksacks = get_rucksacks_from_file("./input.txt");
+        rucksacks.reverse();
+        let groups = find_badge_groups(&rucksacks).unwrap();
+
+        assert_is_partition(&rucksacks, &groups);
+    }
+
+    #[test]
+    fn it_proves_there_is_no_grouping() {
+        let rucksacks = ["abc", "ade", "afg", "hij", "hkl", "xyz"]
+            .map(|items| ItemSet::from_items(items).unwrap());
+        assert_eq!(find_badge_groups(&rucksacks), None);
+
+        let rucksacks = ["abc", "ade"].map(|items| ItemSet::from_items(items).unwrap());
+        assert_eq!(find_badge_groups(&rucksacks), None);
+    }
+}
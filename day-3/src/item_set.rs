@@ -0,0 +1,157 @@
+use std::fmt;
+use std::ops::{BitAnd, BitOr};
+
+// Bit n is set when the item with priority n + 1 is present, so a-z use bits 0-25 and A-Z use bits 26-51
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct ItemSet(u64);
+
+const INDEX_BIT_MASKS: [u64; 6] = [
+    0xAAAA_AAAA_AAAA_AAAA,
+    0xCCCC_CCCC_CCCC_CCCC,
+    0xF0F0_F0F0_F0F0_F0F0,
+    0xFF00_FF00_FF00_FF00,
+    0xFFFF_0000_FFFF_0000,
+    0xFFFF_FFFF_0000_0000,
+];
+
+pub fn get_priority_for_item(item: u8) -> Option<u32> {
+    match item {
+        b'a'..=b'z' => Some((item - b'a') as u32 + 1),
+        b'A'..=b'Z' => Some((item - b'A') as u32 + 27),
+        _ => None,
+    }
+}
+
+// The items that aren't letters, in the order they came, along with the set of the ones that are
+#[derive(Debug, PartialEq, Eq)]
+pub struct InvalidItems {
+    pub valid_items: ItemSet,
+    pub invalid_items: Vec<char>,
+}
+
+impl ItemSet {
+    pub fn from_items(items: &str) -> Result<ItemSet, InvalidItems> {
+        ItemSet::from_chars(items.chars())
+    }
+
+    pub fn from_chars(items: impl IntoIterator<Item = char>) -> Result<ItemSet, InvalidItems> {
+        let mut item_set = ItemSet::default();
+        let mut invalid_items: Vec<char> = Vec::new();
+        for item in items {
+            if !item.is_ascii() || !item_set.insert(item as u8) {
+                invalid_items.push(item);
+            }
+        }
+
+        if invalid_items.is_empty() {
+            Ok(item_set)
+        } else {
+            Err(InvalidItems {
+                valid_items: item_set,
+                invalid_items,
+            })
+        }
+    }
+
+    pub fn insert(&mut self, item: u8) -> bool {
+        match get_priority_for_item(item) {
+            Some(priority) => {
+                self.0 |= 1 << (priority - 1);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn len(&self) -> u32 {
+        self.0.count_ones()
+    }
+
+    pub fn items(&self) -> impl Iterator<Item = char> {
+        let mut bits = self.0;
+        std::iter::from_fn(move || {
+            if bits == 0 {
+                return None;
+            }
+            let bit = bits.trailing_zeros() as u8;
+            bits &= bits - 1;
+            Some(match bit {
+                0..=25 => (b'a' + bit) as char,
+                _ => (b'A' + bit - 26) as char,
+            })
+        })
+    }
+
+    pub fn get_priority(&self) -> u32 {
+        // Each item's priority is its bit index plus one, so add up the set bits of every index bit
+        INDEX_BIT_MASKS
+            .iter()
+            .enumerate()
+            .map(|(index_bit, mask)| (self.0 & mask).count_ones() << index_bit)
+            .sum::<u32>()
+            + self.0.count_ones()
+    }
+}
+
+impl fmt::Display for ItemSet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.items().collect::<String>())
+    }
+}
+
+impl BitAnd for ItemSet {
+    type Output = ItemSet;
+
+    fn bitand(self, other: ItemSet) -> ItemSet {
+        ItemSet(self.0 & other.0)
+    }
+}
+
+impl BitOr for ItemSet {
+    type Output = ItemSet;
+
+    fn bitor(self, other: ItemSet) -> ItemSet {
+        ItemSet(self.0 | other.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_returns_expected_priorities_for_items() {
+        assert_eq!(get_priority_for_item(b'a'), Some(1));
+        assert_eq!(get_priority_for_item(b'z'), Some(26));
+        assert_eq!(get_priority_for_item(b'A'), Some(27));
+        assert_eq!(get_priority_for_item(b'Z'), Some(52));
+        assert_eq!(get_priority_for_item(b'1'), None);
+    }
+
+    #[test]
+    fn it_intersects_item_sets() {
+        let first = ItemSet::from_items("vJrwpWtwJgWr").unwrap();
+        let second = ItemSet::from_items("hcsFMMfFFhFp").unwrap();
+        let shared = first & second;
+
+        assert_eq!(shared.to_string(), "p");
+        assert_eq!(shared.get_priority(), 16);
+        assert_eq!(
+            ItemSet::from_items("abc").unwrap() & ItemSet::from_items("XYZ").unwrap(),
+            ItemSet::default()
+        );
+        assert_eq!(ItemSet::from_items("aA").unwrap().get_priority(), 1 + 27);
+    }
+
+    #[test]
+    fn it_reports_items_that_are_not_letters() {
+        assert_eq!(
+            ItemSet::from_items("a1b é-a"),
+            Err(InvalidItems {
+                valid_items: ItemSet::from_items("ab").unwrap(),
+                invalid_items: vec!['1', ' ', 'é', '-']
+            })
+        );
+        assert_eq!(ItemSet::from_items(""), Ok(ItemSet::default()));
+    }
+}
@@ -1,133 +1,225 @@
 extern crate core;
 
-use itertools::Itertools;
-use std::fs;
+mod audit;
+mod bench;
+mod grouping;
+mod item_set;
+mod packing;
+mod rearrangement;
+
+use item_set::ItemSet;
+use packing::{get_common_items, get_item_sets, split_into_compartments, PackingError};
+use std::{env, fs, process};
+
+fn get_shared_items(file_path: &str, compartments: usize) -> Result<Vec<ItemSet>, PackingError> {
+    if compartments == 0 {
+        return Err(PackingError::NoCompartments);
+    }
 
-const ASCII_LOWER: [char; 26] = [
-    'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r', 's',
-    't', 'u', 'v', 'w', 'x', 'y', 'z',
-];
+    let file_contents =
+        fs::read_to_string(file_path).expect("Should have been able to read the file");
 
-fn get_shared_letter_from_codes(codes: &(String, String)) -> char {
-    let mut shared_letter: Option<char> = None;
+    file_contents
+        .lines()
+        .enumerate()
+        .map(|(index, line)| {
+            let compartment_items = split_into_compartments(line, compartments).ok_or(
+                PackingError::UnevenCompartments {
+                    line_number: index + 1,
+                    length: line.chars().count(),
+                    compartments,
+                },
+            )?;
+            Ok(get_common_items(&get_item_sets(
+                index + 1,
+                &compartment_items,
+            )?))
+        })
+        .collect()
+}
 
-    for letter_in_code_one in codes.0.chars() {
-        if codes.1.contains(letter_in_code_one) {
-            shared_letter = Option::from(letter_in_code_one);
-        }
+fn get_rucksacks(file_contents: &str) -> Result<Vec<ItemSet>, PackingError> {
+    file_contents
+        .lines()
+        .enumerate()
+        .map(|(index, line)| Ok(get_item_sets(index + 1, &[line])?[0]))
+        .collect()
+}
 
-        if let Some(_char) = shared_letter {
-            break;
-        }
+fn get_badges(file_path: &str, group_size: usize) -> Result<Vec<ItemSet>, PackingError> {
+    if group_size == 0 {
+        return Err(PackingError::EmptyGroups);
     }
 
-    return shared_letter.unwrap();
-}
+    let file_contents =
+        fs::read_to_string(file_path).expect("Should have been able to read the file");
 
-fn get_common_letter_in_string(strings: &Vec<&str>) -> char {
-    let first_code = strings[0];
-    let second_code = strings[1];
-    let third_code = strings[2];
+    let rucksacks = get_rucksacks(&file_contents)?;
 
-    for letter in first_code.chars() {
-        if second_code.contains(letter) && third_code.contains(letter) {
-            return letter;
-        }
+    if !rucksacks.len().is_multiple_of(group_size) {
+        return Err(PackingError::IncompleteGroup {
+            rucksacks: rucksacks.len(),
+            group_size,
+        });
     }
 
-    panic!("No common letters found!");
+    Ok(rucksacks.chunks(group_size).map(get_common_items).collect())
 }
 
-fn to_chunks(string: &str, chunk_size: usize) -> (String, String) {
-    let mut sections: (String, String) = ("".to_string(), "".to_string());
+fn get_sum_of_priorities(file_path: &str) -> u32 {
+    get_shared_items(file_path, 2)
+        .expect("Rucksacks should have two equal compartments")
+        .iter()
+        .map(ItemSet::get_priority)
+        .sum()
+}
 
-    let mut index = 0;
-    for chunk in &string.chars().chunks(chunk_size) {
-        let string_from_chunk = String::from_iter(chunk);
-        if index % 2 == 0 {
-            sections.0 = string_from_chunk
-        } else {
-            sections.1 = string_from_chunk
+fn get_sum_of_priorities_by_group(file_path: &str) -> u32 {
+    get_badges(file_path, 3)
+        .expect("Rucksacks should be in groups of three")
+        .iter()
+        .map(ItemSet::get_priority)
+        .sum()
+}
+
+fn print_item_sets(description: &str, item_sets: Result<Vec<ItemSet>, PackingError>) {
+    match item_sets {
+        Ok(item_sets) => {
+            let common_items: Vec<String> = item_sets.iter().map(ItemSet::to_string).collect();
+            println!(
+                "{}: {} [{}]",
+                description,
+                item_sets.iter().map(ItemSet::get_priority).sum::<u32>(),
+                common_items.join(", ")
+            );
         }
-        index += 1;
+        Err(error) => println!("{}: {}", description, error),
     }
-
-    return sections;
 }
 
-fn get_priority_for_char(char: &char) -> i16 {
-    let mut base_priority: i16 = 0;
+fn print_audit_report(file_path: &str) -> i32 {
+    let file_contents =
+        fs::read_to_string(file_path).expect("Should have been able to read the file");
 
-    if char.is_uppercase() {
-        base_priority = 26;
+    let report = audit::audit_rucksacks(&file_contents);
+    for anomaly in &report.anomalies {
+        println!("{}", anomaly);
     }
+    println!("{} anomalies found", report.anomalies.len());
+    println!("Part 1: {}", report.sum_of_priorities);
+    println!("Part 2: {}", report.sum_of_priorities_by_group);
 
-    let letter_to_find: String = char.to_lowercase().to_string();
-
-    let index = ASCII_LOWER
-        .iter()
-        .position(|letter| letter.to_string() == letter_to_find)
-        .unwrap() as i16;
-
-    return index + 1 + base_priority;
+    report.exit_code()
 }
 
-fn get_sum_of_priorities(file_path: &str) -> i16 {
+fn print_badge_groups(file_path: &str) {
     let file_contents =
         fs::read_to_string(file_path).expect("Should have been able to read the file");
 
-    let file_contents_by_line = file_contents.lines();
-
-    let mut codes: Vec<(String, String)> = Vec::new();
-
-    for line in file_contents_by_line {
-        codes.push(to_chunks(line, line.len() / 2));
+    let rucksacks = match get_rucksacks(&file_contents) {
+        Ok(rucksacks) => rucksacks,
+        Err(error) => {
+            println!("{}", error);
+            return;
+        }
+    };
+
+    match grouping::find_badge_groups(&rucksacks) {
+        Some(groups) => {
+            for group in &groups {
+                let [first, second, third] = group.rucksacks.map(|rucksack| rucksack + 1);
+                println!(
+                    "Lines {}, {}, {}: {} ({})",
+                    first,
+                    second,
+                    third,
+                    group.badge,
+                    group.badge.get_priority()
+                );
+            }
+            let sum_of_priorities: u32 =
+                groups.iter().map(|group| group.badge.get_priority()).sum();
+            println!("Part 2: {}", sum_of_priorities);
+        }
+        None => println!("No grouping gives every group exactly one badge"),
     }
-
-    let shared_letters = codes
-        .iter()
-        .map(|codes| get_shared_letter_from_codes(codes))
-        .collect::<Vec<char>>();
-
-    return shared_letters
-        .iter()
-        .map(|letter| get_priority_for_char(letter))
-        .sum::<i16>();
 }
 
-fn get_sum_of_priorities_by_group(file_path: &str) -> i16 {
+fn print_rearrangement_plans(file_path: &str) {
     let file_contents =
         fs::read_to_string(file_path).expect("Should have been able to read the file");
 
-    let file_contents_by_line = file_contents.lines().chunks(3);
-
-    let group_codes = file_contents_by_line
-        .into_iter()
-        .map(|line| Vec::from_iter(line))
-        .collect::<Vec<Vec<&str>>>();
-
-    let common_letters = group_codes
-        .iter()
-        .map(|group_code| get_common_letter_in_string(group_code))
-        .collect::<Vec<char>>();
+    let mut plans: Vec<rearrangement::Plan> = Vec::new();
+    for (index, line) in file_contents.lines().enumerate() {
+        match rearrangement::plan_rearrangement(line) {
+            Some(plan) => {
+                println!("Line {}: {}", index + 1, plan);
+                plans.push(plan);
+            }
+            None => println!(
+                "Line {}: can't be split into equal compartments of valid items, skipped",
+                index + 1
+            ),
+        }
+    }
 
-    return common_letters
-        .iter()
-        .map(|letter| get_priority_for_char(letter))
-        .sum::<i16>();
+    let summary = rearrangement::summarise(&plans);
+    println!(
+        "{} rucksacks: {} already packed, {} swaps, {} removals across {} rucksacks",
+        summary.rucksacks,
+        summary.already_packed,
+        summary.swaps,
+        summary.removals,
+        summary.rucksacks_needing_removals
+    );
 }
 
 fn main() {
-    let sum_of_priorities = get_sum_of_priorities("./input.txt");
-    println!("Part 1: {}", sum_of_priorities);
-    let sum_of_priorities_by_group = get_sum_of_priorities_by_group("./input.txt");
-    println!("Part 2: {}", sum_of_priorities_by_group);
+    let arguments: Vec<String> = env::args().skip(1).collect();
+
+    match arguments.iter().map(String::as_str).collect::<Vec<&str>>()[..] {
+        // cargo run --release -- bench
+        ["bench"] => bench::run_benchmarks(),
+        // cargo run -- audit [file]
+        ["audit"] => process::exit(print_audit_report("./input.txt")),
+        ["audit", file_path] => process::exit(print_audit_report(file_path)),
+        // cargo run -- shuffled [file]
+        ["shuffled"] => print_badge_groups("./input.txt"),
+        ["shuffled", file_path] => print_badge_groups(file_path),
+        // cargo run -- plan [file]
+        ["plan"] => print_rearrangement_plans("./input.txt"),
+        ["plan", file_path] => print_rearrangement_plans(file_path),
+        // cargo run -- <compartments> <group size>
+        [compartments, group_size] => {
+            let compartments = compartments
+                .parse()
+                .expect("Compartments should be a number");
+            let group_size = group_size.parse().expect("Group size should be a number");
+            print_item_sets(
+                &format!("Items shared by {} compartments", compartments),
+                get_shared_items("./input.txt", compartments),
+            );
+            print_item_sets(
+                &format!("Badges for groups of {}", group_size),
+                get_badges("./input.txt", group_size),
+            );
+        }
+        _ => {
+            let sum_of_priorities = get_sum_of_priorities("./input.txt");
+            println!("Part 1: {}", sum_of_priorities);
+            let sum_of_priorities_by_group = get_sum_of_priorities_by_group("./input.txt");
+            println!("Part 2: {}", sum_of_priorities_by_group);
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::get_badges;
+    use crate::get_shared_items;
     use crate::get_sum_of_priorities;
     use crate::get_sum_of_priorities_by_group;
+    use crate::PackingError;
 
     #[test]
     fn it_returns_expected_result_for_test_file() {
@@ -151,4 +243,50 @@ mod tests {
         let sum_of_priorities = get_sum_of_priorities_by_group("./input.txt");
         assert_eq!(sum_of_priorities, 2639);
     }
+
+    #[test]
+    fn it_returns_common_item_sets_for_other_configurations() {
+        let shared_items = get_shared_items("./test.txt", 1).unwrap();
+        assert_eq!(shared_items[0].to_string(), "cfghprstvwFJMW");
+
+        let badges = get_badges("./test.txt", 6).unwrap();
+        assert_eq!(badges.len(), 1);
+        assert_eq!(badges[0].to_string(), "");
+
+        let badges = get_badges("./test.txt", 2).unwrap();
+        assert_eq!(badges[0].to_string(), "frsFM");
+    }
+
+    #[test]
+    fn it_rejects_configurations_that_do_not_divide_evenly() {
+        assert_eq!(
+            get_shared_items("./test.txt", 3),
+            Err(PackingError::UnevenCompartments {
+                line_number: 2,
+                length: 32,
+                compartments: 3
+            })
+        );
+        assert_eq!(
+            get_badges("./test.txt", 4),
+            Err(PackingError::IncompleteGroup {
+                rucksacks: 6,
+                group_size: 4
+            })
+        );
+        assert_eq!(
+            get_shared_items("./test.txt", 0),
+            Err(PackingError::NoCompartments)
+        );
+    }
+
+    #[test]
+    fn it_rejects_items_that_are_not_letters() {
+        let invalid_items = Err(PackingError::InvalidItems {
+            line_number: 5,
+            items: vec!['1', '2'],
+        });
+        assert_eq!(get_badges("./audit.txt", 1), invalid_items);
+        assert_eq!(get_shared_items("./audit.txt", 1), invalid_items);
+    }
 }
@@ -0,0 +1,151 @@
+use crate::item_set::ItemSet;
+use std::fmt;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum PackingError {
+    NoCompartments,
+    EmptyGroups,
+    UnevenCompartments {
+        line_number: usize,
+        length: usize,
+        compartments: usize,
+    },
+    IncompleteGroup {
+        rucksacks: usize,
+        group_size: usize,
+    },
+    InvalidItems {
+        line_number: usize,
+        items: Vec<char>,
+    },
+}
+
+impl fmt::Display for PackingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PackingError::NoCompartments => write!(f, "Rucksacks need at least one compartment"),
+            PackingError::EmptyGroups => write!(f, "Groups need at least one rucksack"),
+            PackingError::UnevenCompartments {
+                line_number,
+                length,
+                compartments,
+            } => write!(
+                f,
+                "Line {}: {} items can't be split into {} equal compartments",
+                line_number, length, compartments
+            ),
+            PackingError::IncompleteGroup {
+                rucksacks,
+                group_size,
+            } => write!(
+                f,
+                "{} rucksacks can't be split into groups of {}",
+                rucksacks, group_size
+            ),
+            PackingError::InvalidItems { line_number, items } => write!(
+                f,
+                "Line {}: invalid items {:?}",
+                line_number,
+                items.iter().collect::<String>()
+            ),
+        }
+    }
+}
+
+// Splits by items rather than bytes, so an invalid item that takes more than one byte stays whole
+pub fn split_into_compartments(rucksack: &str, compartments: usize) -> Option<Vec<&str>> {
+    let length = rucksack.chars().count();
+    if compartments == 0 || !length.is_multiple_of(compartments) {
+        return None;
+    }
+
+    let compartment_size = (length / compartments).max(1);
+    let boundaries: Vec<usize> = rucksack
+        .char_indices()
+        .map(|(index, _)| index)
+        .step_by(compartment_size)
+        .chain([rucksack.len()])
+        .collect();
+
+    Some(
+        boundaries
+            .windows(2)
+            .map(|bounds| &rucksack[bounds[0]..bounds[1]])
+            .collect(),
+    )
+}
+
+// Every compartment or rucksack as a set, with the invalid items of all of them if there are any
+pub fn get_item_sets(line_number: usize, parts: &[&str]) -> Result<Vec<ItemSet>, PackingError> {
+    let mut invalid_items: Vec<char> = Vec::new();
+    let item_sets: Vec<ItemSet> = parts
+        .iter()
+        .map(|part| {
+            ItemSet::from_items(part).unwrap_or_else(|error| {
+                invalid_items.extend(error.invalid_items);
+                error.valid_items
+            })
+        })
+        .collect();
+
+    if invalid_items.is_empty() {
+        Ok(item_sets)
+    } else {
+        Err(PackingError::InvalidItems {
+            line_number,
+            items: invalid_items,
+        })
+    }
+}
+
+pub fn get_common_items(item_sets: &[ItemSet]) -> ItemSet {
+    item_sets
+        .iter()
+        .copied()
+        .reduce(|common_items, items| common_items & items)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_splits_rucksacks_into_equal_compartments() {
+        let compartments = split_into_compartments("abcABCxyz", 3).unwrap();
+        assert_eq!(compartments, ["abc", "ABC", "xyz"]);
+        assert_eq!(split_into_compartments("aébç", 2).unwrap(), ["aé", "bç"]);
+
+        assert_eq!(split_into_compartments("abcd", 3), None);
+        assert_eq!(split_into_compartments("abcd", 0), None);
+    }
+
+    #[test]
+    fn it_reports_every_invalid_item_in_a_line() {
+        assert_eq!(
+            get_item_sets(4, &["ab", "AB"]),
+            Ok(vec![
+                ItemSet::from_items("ab").unwrap(),
+                ItemSet::from_items("AB").unwrap()
+            ])
+        );
+        assert_eq!(
+            get_item_sets(4, &["a1", "é-"]),
+            Err(PackingError::InvalidItems {
+                line_number: 4,
+                items: vec!['1', 'é', '-']
+            })
+        );
+    }
+
+    #[test]
+    fn it_returns_every_common_item() {
+        let item_sets = [
+            ItemSet::from_items("abcX").unwrap(),
+            ItemSet::from_items("bcdX").unwrap(),
+            ItemSet::from_items("Xcbe").unwrap(),
+        ];
+        assert_eq!(get_common_items(&item_sets).to_string(), "bcX");
+        assert_eq!(get_common_items(&[]), ItemSet::default());
+    }
+}
@@ -0,0 +1,256 @@
+use crate::item_set::get_priority_for_item;
+use std::fmt;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Fix {
+    // (index in the first compartment, index in the second compartment) of each pair of items to exchange
+    Swaps(Vec<(usize, usize)>),
+    Removals(Vec<char>),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Plan {
+    pub before: (String, String),
+    pub after: (String, String),
+    pub fix: Fix,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Summary {
+    pub rucksacks: usize,
+    pub already_packed: usize,
+    pub swaps: usize,
+    pub rucksacks_needing_removals: usize,
+    pub removals: usize,
+}
+
+fn get_type(item: char) -> usize {
+    get_priority_for_item(item as u8).expect("Invalid item") as usize - 1
+}
+
+fn is_valid_item(item: &char) -> bool {
+    item.is_ascii() && get_priority_for_item(*item as u8).is_some()
+}
+
+// Picks which item types should end up in the first compartment. Returns the types as a mask and
+// the total count of their items, which is as close to the compartment size as possible, preferring
+// the choice that leaves the fewest items in the wrong compartment
+fn choose_first_compartment_types(first: &[char], second: &[char]) -> (u64, usize) {
+    let mut counts_in_first = [0; 52];
+    let mut counts_in_second = [0; 52];
+    for item in first {
+        counts_in_first[get_type(*item)] += 1;
+    }
+    for item in second {
+        counts_in_second[get_type(*item)] += 1;
+    }
+
+    let total = first.len() + second.len();
+    let types: Vec<usize> = (0..52)
+        .filter(|item_type| counts_in_first[*item_type] + counts_in_second[*item_type] > 0)
+        .collect();
+
+    // best[n][w]: fewest items that have to move into the first compartment when w items of the
+    // first n types are assigned to it, and whether the nth type was assigned to get there
+    let mut best: Vec<Vec<Option<usize>>> = vec![vec![None; total + 1]; types.len() + 1];
+    let mut is_taken: Vec<Vec<bool>> = vec![vec![false; total + 1]; types.len() + 1];
+    best[0][0] = Some(0);
+
+    for (index, item_type) in types.iter().enumerate() {
+        let count = counts_in_first[*item_type] + counts_in_second[*item_type];
+        for weight in 0..=total {
+            let skipped = best[index][weight];
+            let taken = weight
+                .checked_sub(count)
+                .and_then(|previous| best[index][previous])
+                .map(|moves| moves + counts_in_second[*item_type]);
+
+            if let Some(taken) = taken {
+                if skipped.is_none_or(|skipped| taken < skipped) {
+                    best[index + 1][weight] = Some(taken);
+                    is_taken[index + 1][weight] = true;
+                    continue;
+                }
+            }
+            best[index + 1][weight] = skipped;
+        }
+    }
+
+    let half = total / 2;
+    let weight = (0..=total)
+        .filter(|weight| best[types.len()][*weight].is_some())
+        .min_by_key(|weight| (weight.abs_diff(half), best[types.len()][*weight]))
+        .unwrap();
+
+    let mut mask = 0;
+    let mut remaining = weight;
+    for (index, item_type) in types.iter().enumerate().rev() {
+        if is_taken[index + 1][remaining] {
+            mask |= 1 << item_type;
+            remaining -= counts_in_first[*item_type] + counts_in_second[*item_type];
+        }
+    }
+
+    (mask, weight)
+}
+
+pub fn plan_rearrangement(rucksack: &str) -> Option<Plan> {
+    let items: Vec<char> = rucksack.chars().collect();
+    if !items.len().is_multiple_of(2) || !items.iter().all(is_valid_item) {
+        return None;
+    }
+
+    let (first, second) = items.split_at(items.len() / 2);
+    let before = (first.iter().collect(), second.iter().collect());
+    let (mask, weight) = choose_first_compartment_types(first, second);
+    let belongs_in_first = |item: &char| mask & 1 << get_type(*item) != 0;
+
+    if weight == first.len() {
+        let misplaced_in_first = (0..first.len()).filter(|index| !belongs_in_first(&first[*index]));
+        let misplaced_in_second =
+            (0..second.len()).filter(|index| belongs_in_first(&second[*index]));
+        let swaps: Vec<(usize, usize)> = misplaced_in_first.zip(misplaced_in_second).collect();
+
+        let mut first_after = first.to_vec();
+        let mut second_after = second.to_vec();
+        for (first_index, second_index) in &swaps {
+            std::mem::swap(
+                &mut first_after[*first_index],
+                &mut second_after[*second_index],
+            );
+        }
+
+        return Some(Plan {
+            before,
+            after: (first_after.iter().collect(), second_after.iter().collect()),
+            fix: Fix::Swaps(swaps),
+        });
+    }
+
+    // No split keeps the compartments the same size, so drop the surplus from the larger side
+    let mut first_after: Vec<char> = items.iter().copied().filter(belongs_in_first).collect();
+    let mut second_after: Vec<char> = items
+        .iter()
+        .copied()
+        .filter(|item| !belongs_in_first(item))
+        .collect();
+    let compartment_size = first_after.len().min(second_after.len());
+
+    let mut removed: Vec<char> = first_after.split_off(compartment_size);
+    removed.extend(second_after.split_off(compartment_size));
+
+    Some(Plan {
+        before,
+        after: (first_after.iter().collect(), second_after.iter().collect()),
+        fix: Fix::Removals(removed),
+    })
+}
+
+pub fn summarise(plans: &[Plan]) -> Summary {
+    plans.iter().fold(
+        Summary {
+            rucksacks: plans.len(),
+            ..Summary::default()
+        },
+        |mut summary, plan| {
+            match &plan.fix {
+                Fix::Swaps(swaps) if swaps.is_empty() => summary.already_packed += 1,
+                Fix::Swaps(swaps) => summary.swaps += swaps.len(),
+                Fix::Removals(removed) => {
+                    summary.rucksacks_needing_removals += 1;
+                    summary.removals += removed.len();
+                }
+            }
+            summary
+        },
+    )
+}
+
+fn get_diff_markers(before: &str, after: &str) -> String {
+    let mut markers: String = before
+        .chars()
+        .zip(after.chars())
+        .map(|(before, after)| if before == after { ' ' } else { '^' })
+        .collect();
+    markers.extend(std::iter::repeat_n('^', before.len().abs_diff(after.len())));
+    markers
+}
+
+impl fmt::Display for Plan {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.fix {
+            Fix::Swaps(swaps) if swaps.len() == 1 => writeln!(f, "1 swap")?,
+            Fix::Swaps(swaps) => writeln!(f, "{} swaps", swaps.len())?,
+            Fix::Removals(removed) => writeln!(
+                f,
+                "no equal split, remove {} items ({})",
+                removed.len(),
+                removed.iter().collect::<String>()
+            )?,
+        }
+
+        let width = self.before.0.len().max(self.after.0.len());
+        writeln!(f, "  before: {:width$} | {}", self.before.0, self.before.1)?;
+        writeln!(f, "  after:  {:width$} | {}", self.after.0, self.after.1)?;
+        write!(
+            f,
+            "          {:width$}   {}",
+            get_diff_markers(&self.before.0, &self.after.0),
+            get_diff_markers(&self.before.1, &self.after.1)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn has_shared_items(plan: &Plan) -> bool {
+        plan.after.0.chars().any(|item| plan.after.1.contains(item))
+    }
+
+    #[test]
+    fn it_plans_the_fewest_swaps() {
+        let plan = plan_rearrangement("vJrwpWtwJgWrhcsFMMfFFhFp").unwrap();
+        assert_eq!(plan.fix, Fix::Swaps(vec![(0, 11)]));
+        assert_eq!(
+            plan.after,
+            ("pJrwpWtwJgWr".to_string(), "hcsFMMfFFhFv".to_string())
+        );
+
+        let plan = plan_rearrangement("ttgJtRGJQctTZtZT").unwrap();
+        assert!(matches!(&plan.fix, Fix::Swaps(swaps) if swaps.len() == 2));
+        assert!(!has_shared_items(&plan));
+
+        let plan = plan_rearrangement("abcdef").unwrap();
+        assert_eq!(plan.fix, Fix::Swaps(vec![]));
+        assert_eq!(plan.after, plan.before);
+    }
+
+    #[test]
+    fn it_plans_removals_when_no_equal_split_exists() {
+        let plan = plan_rearrangement("aaab").unwrap();
+        assert_eq!(plan.fix, Fix::Removals(vec!['a', 'a']));
+        assert_eq!(plan.after, ("b".to_string(), "a".to_string()));
+
+        assert_eq!(plan_rearrangement("abc"), None);
+        assert_eq!(plan_rearrangement("ab1cb2"), None);
+    }
+
+    #[test]
+    fn it_summarises_plans_for_input_file() {
+        let file_contents =
+            fs::read_to_string("./input.txt").expect("Should have been able to read the file");
+        let plans: Vec<Plan> = file_contents
+            .lines()
+            .filter_map(plan_rearrangement)
+            .collect();
+
+        assert!(plans.iter().all(|plan| !has_shared_items(plan)));
+
+        let summary = summarise(&plans);
+        assert_eq!(summary.rucksacks, 300);
+        assert_eq!(summary.already_packed, 0);
+    }
+}
@@ -0,0 +1,198 @@
+use crate::coverage::{get_coverage_report, Assignment, CoverageReport, Redundancy};
+use crate::interval::Interval;
+use std::cmp::Reverse;
+use std::time::Instant;
+
+const NUMBER_OF_LINES: usize = 500_000;
+const NUMBER_OF_SECTIONS: u64 = 1_000_000;
+const LONGEST_ASSIGNMENT: u64 = 1_000;
+
+struct Random(u64);
+
+impl Random {
+    fn next(&mut self) -> u64 {
+        // xorshift64, so generated inputs are the same on every run
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, limit: u64) -> u64 {
+        self.next() % limit
+    }
+}
+
+fn generate_assignments(
+    random: &mut Random,
+    number_of_lines: usize,
+    number_of_sections: u64,
+    longest_assignment: u64,
+) -> Vec<Assignment> {
+    (1..=number_of_lines)
+        .flat_map(|line_number| [(line_number, 0), (line_number, 1)])
+        .map(|(line_number, elf)| {
+            let start = random.below(number_of_sections) + 1;
+            let end = (start + random.below(longest_assignment)).min(number_of_sections);
+            Assignment {
+                line_number,
+                elf,
+                sections: Interval::closed(start as i32, end as i32),
+            }
+        })
+        .collect()
+}
+
+fn time<T>(description: &str, run: impl Fn() -> T) -> T {
+    let start = Instant::now();
+    let result = run();
+    println!("{}: {:?}", description, start.elapsed());
+    result
+}
+
+// Counts the elves on every section one assignment at a time, which is what the sweep avoids
+fn get_coverage_report_by_counting(
+    assignments: &[Assignment],
+    more_than: usize,
+    top: usize,
+) -> CoverageReport {
+    let first_section = assignments
+        .iter()
+        .map(|assignment| assignment.sections.start())
+        .min()
+        .unwrap_or(1)
+        .min(1);
+    let last_section = assignments
+        .iter()
+        .map(|assignment| assignment.sections.end())
+        .max()
+        .unwrap_or(0);
+    let camp = Interval::closed(first_section, last_section);
+
+    let mut elves: Vec<usize> = vec![0; camp.len() as usize];
+    for assignment in assignments {
+        for section in assignment.sections.start()..=assignment.sections.end() {
+            elves[(section - first_section) as usize] += 1;
+        }
+    }
+
+    let get_runs = |is_included: &dyn Fn(usize) -> bool| {
+        let mut runs: Vec<Interval<i32>> = Vec::new();
+        for (index, count) in elves.iter().enumerate() {
+            if !is_included(*count) {
+                continue;
+            }
+            let section = first_section + index as i32;
+            match runs.last_mut() {
+                Some(run) if run.end() == section - 1 => {
+                    *run = Interval::closed(run.start(), section)
+                }
+                _ => runs.push(Interval::closed(section, section)),
+            }
+        }
+        runs
+    };
+
+    let mut most_redundant: Vec<Redundancy> = assignments
+        .iter()
+        .map(|assignment| Redundancy {
+            assignment: *assignment,
+            shared_sections: (assignment.sections.start()..=assignment.sections.end())
+                .filter(|section| elves[(section - first_section) as usize] > 1)
+                .count() as u64,
+        })
+        .collect();
+    most_redundant.sort_by_key(|redundancy| {
+        (
+            Reverse(redundancy.shared_sections),
+            redundancy.assignment.line_number,
+            redundancy.assignment.elf,
+        )
+    });
+    most_redundant.truncate(top);
+
+    let assigned_sections: u64 = elves.iter().map(|count| *count as u64).sum();
+    let covered_sections = elves.iter().filter(|count| **count > 0).count() as u64;
+
+    CoverageReport {
+        camp,
+        uncovered: get_runs(&|count| count == 0),
+        crowded: get_runs(&|count| count > more_than),
+        most_elves_on_a_section: elves.iter().copied().max().unwrap_or(0),
+        assigned_sections,
+        covered_sections,
+        duplicated_effort: assigned_sections - covered_sections,
+        most_redundant,
+    }
+}
+
+pub fn run_benchmarks() {
+    let assignments = generate_assignments(
+        &mut Random(0x2022_1204),
+        NUMBER_OF_LINES,
+        NUMBER_OF_SECTIONS,
+        LONGEST_ASSIGNMENT,
+    );
+    println!("Generated {} assignments", assignments.len());
+
+    let by_counting = time("Coverage by counting every section", || {
+        get_coverage_report_by_counting(&assignments, 3, 10)
+    });
+    let by_sweep = time("Coverage by sweeping endpoints", || {
+        get_coverage_report(&assignments, 3, 10)
+    });
+    assert_eq!(by_counting, by_sweep);
+    println!(
+        "Reports match: {} sections duplicated",
+        by_sweep.duplicated_effort
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::get_assignments_from_file;
+    use proptest::prelude::*;
+
+    #[test]
+    fn it_returns_same_report_for_input_file() {
+        let assignments = get_assignments_from_file("./input.txt");
+
+        assert_eq!(
+            get_coverage_report_by_counting(&assignments, 2, 10),
+            get_coverage_report(&assignments, 2, 10)
+        );
+    }
+
+    // Up to 20 lines of two elves, each on up to 15 of 60 sections
+    fn any_assignments() -> impl Strategy<Value = Vec<Assignment>> {
+        prop::collection::vec([(1..=60i32, 0..15i32), (1..=60i32, 0..15i32)], 1..=20).prop_map(
+            |lines| {
+                lines
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(index, elves)| {
+                        elves
+                            .iter()
+                            .enumerate()
+                            .map(move |(elf, (start, length))| Assignment {
+                                line_number: index + 1,
+                                elf,
+                                sections: Interval::closed(*start, (start + length).min(60)),
+                            })
+                    })
+                    .collect()
+            },
+        )
+    }
+
+    proptest! {
+        #[test]
+        fn it_returns_same_report_for_generated_assignments(assignments in any_assignments()) {
+            prop_assert_eq!(
+                get_coverage_report_by_counting(&assignments, 2, 5),
+                get_coverage_report(&assignments, 2, 5)
+            );
+        }
+    }
+}
@@ -0,0 +1,284 @@
+use crate::interval::Interval;
+use std::cmp::Reverse;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Assignment {
+    pub line_number: usize,
+    // 0 for the first elf on the line, 1 for the second
+    pub elf: usize,
+    pub sections: Interval<i32>,
+}
+
+// A run of sections that are all covered by the same number of elves
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Segment {
+    pub sections: Interval<i32>,
+    pub elves: usize,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Redundancy {
+    pub assignment: Assignment,
+    // Sections of the assignment that at least one other elf also covers
+    pub shared_sections: u64,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct CoverageReport {
+    pub camp: Interval<i32>,
+    pub uncovered: Vec<Interval<i32>>,
+    pub crowded: Vec<Interval<i32>>,
+    pub most_elves_on_a_section: usize,
+    pub assigned_sections: u64,
+    pub covered_sections: u64,
+    pub duplicated_effort: u64,
+    pub most_redundant: Vec<Redundancy>,
+}
+
+struct Sweep {
+    // In order, with no gaps from the first covered section to the last
+    segments: Vec<Segment>,
+    // For each assignment, how many of its sections at least one other elf also covers
+    shared_sections: Vec<u64>,
+}
+
+// Sweeps over the assignment endpoints, so this is O(n log n) however long the assignments are
+fn sweep(assignments: &[Assignment]) -> Sweep {
+    let mut endpoints: Vec<(i32, isize, usize)> = assignments
+        .iter()
+        .enumerate()
+        .filter(|(_, assignment)| !assignment.sections.is_empty())
+        .flat_map(|(index, assignment)| {
+            [
+                (assignment.sections.start(), 1, index),
+                (assignment.sections.exclusive_end(), -1, index),
+            ]
+        })
+        .collect();
+    endpoints.sort_unstable_by_key(|(position, _, _)| *position);
+
+    let mut segments: Vec<Segment> = Vec::new();
+    let mut shared_sections: Vec<u64> = vec![0; assignments.len()];
+    let mut elves: isize = 0;
+    // Sections so far with more than one elf, an assignment's shared sections are the
+    // difference between this at its end and at its start
+    let mut shared_so_far: u64 = 0;
+    let mut index = 0;
+
+    while index < endpoints.len() {
+        let position = endpoints[index].0;
+        while index < endpoints.len() && endpoints[index].0 == position {
+            let (_, change, assignment) = endpoints[index];
+            if change > 0 {
+                shared_sections[assignment] = shared_so_far;
+            } else {
+                shared_sections[assignment] = shared_so_far - shared_sections[assignment];
+            }
+            elves += change;
+            index += 1;
+        }
+
+        if let Some(&(next_position, _, _)) = endpoints.get(index) {
+            let segment = Segment {
+                sections: Interval::closed(position, next_position - 1),
+                elves: elves as usize,
+            };
+            if segment.elves > 1 {
+                shared_so_far += segment.sections.len();
+            }
+            segments.push(segment);
+        }
+    }
+
+    Sweep {
+        segments,
+        shared_sections,
+    }
+}
+
+// Joins touching intervals, which the sweep splits up wherever the number of elves changes
+fn merge_touching(intervals: impl Iterator<Item = Interval<i32>>) -> Vec<Interval<i32>> {
+    let mut merged: Vec<Interval<i32>> = Vec::new();
+    for interval in intervals {
+        if let Some(union) = merged.last().and_then(|last| last.union(&interval)) {
+            *merged.last_mut().unwrap() = union;
+        } else {
+            merged.push(interval);
+        }
+    }
+    merged
+}
+
+fn get_most_redundant(
+    assignments: &[Assignment],
+    shared_sections: &[u64],
+    top: usize,
+) -> Vec<Redundancy> {
+    let mut redundancies: Vec<Redundancy> = assignments
+        .iter()
+        .zip(shared_sections)
+        .filter(|(assignment, _)| !assignment.sections.is_empty())
+        .map(|(assignment, shared_sections)| Redundancy {
+            assignment: *assignment,
+            shared_sections: *shared_sections,
+        })
+        .collect();
+
+    let get_rank = |redundancy: &Redundancy| {
+        (
+            Reverse(redundancy.shared_sections),
+            redundancy.assignment.line_number,
+            redundancy.assignment.elf,
+        )
+    };
+    // Only the top few need sorting, which matters with millions of assignments
+    if top < redundancies.len() {
+        redundancies.select_nth_unstable_by_key(top, get_rank);
+        redundancies.truncate(top);
+    }
+    redundancies.sort_by_key(get_rank);
+    redundancies
+}
+
+// The camp runs from section 1, or the lowest assigned section if that's lower, to the highest
+// assigned section. Crowded sections are those with more than `more_than` elves
+pub fn get_coverage_report(
+    assignments: &[Assignment],
+    more_than: usize,
+    top: usize,
+) -> CoverageReport {
+    let Sweep {
+        segments,
+        shared_sections,
+    } = sweep(assignments);
+
+    let first_section = segments
+        .first()
+        .map_or(1, |segment| segment.sections.start().min(1));
+    let last_section = segments.last().map_or(0, |segment| segment.sections.end());
+    let camp = Interval::closed(first_section, last_section);
+
+    let before_first_assignment = segments
+        .first()
+        .map(|segment| Interval::closed(first_section, segment.sections.start() - 1))
+        .filter(|sections| !sections.is_empty());
+    let uncovered = before_first_assignment
+        .into_iter()
+        .chain(
+            segments
+                .iter()
+                .filter(|segment| segment.elves == 0)
+                .map(|segment| segment.sections),
+        )
+        .collect();
+
+    let crowded = merge_touching(
+        segments
+            .iter()
+            .filter(|segment| segment.elves > more_than)
+            .map(|segment| segment.sections),
+    );
+
+    let (assigned_sections, covered_sections) =
+        segments
+            .iter()
+            .fold((0, 0), |(assigned, covered), segment| {
+                let sections = segment.sections.len();
+                if segment.elves == 0 {
+                    (assigned, covered)
+                } else {
+                    (
+                        assigned + sections * segment.elves as u64,
+                        covered + sections,
+                    )
+                }
+            });
+
+    CoverageReport {
+        camp,
+        uncovered,
+        crowded,
+        most_elves_on_a_section: segments
+            .iter()
+            .map(|segment| segment.elves)
+            .max()
+            .unwrap_or(0),
+        assigned_sections,
+        covered_sections,
+        duplicated_effort: assigned_sections - covered_sections,
+        most_redundant: get_most_redundant(assignments, &shared_sections, top),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::get_assignments_from_file;
+
+    fn to_text(intervals: &[Interval<i32>]) -> Vec<String> {
+        intervals.iter().map(Interval::to_string).collect()
+    }
+
+    #[test]
+    fn it_splits_coverage_into_segments() {
+        let assignments = [(1, 3), (2, 5), (8, 9)].map(|(start, end)| Assignment {
+            line_number: 1,
+            elf: 0,
+            sections: Interval::closed(start, end),
+        });
+        let segments: Vec<(String, usize)> = sweep(&assignments)
+            .segments
+            .iter()
+            .map(|segment| (segment.sections.to_string(), segment.elves))
+            .collect();
+
+        assert_eq!(
+            segments,
+            [
+                ("1-1".to_string(), 1),
+                ("2-3".to_string(), 2),
+                ("4-5".to_string(), 1),
+                ("6-7".to_string(), 0),
+                ("8-9".to_string(), 1),
+            ]
+        );
+        assert_eq!(sweep(&assignments).shared_sections, [2, 2, 0]);
+        assert_eq!(sweep(&[]).segments, []);
+    }
+
+    #[test]
+    fn it_reports_coverage_for_test_file() {
+        let assignments = get_assignments_from_file("./test.txt");
+        let report = get_coverage_report(&assignments, 6, 3);
+
+        assert_eq!(report.camp.to_string(), "1-9");
+        assert_eq!(to_text(&report.uncovered), ["1-1"]);
+        assert_eq!(to_text(&report.crowded), ["4-6"]);
+        assert_eq!(report.most_elves_on_a_section, 8);
+        assert_eq!(report.assigned_sections, 42);
+        assert_eq!(report.covered_sections, 8);
+        assert_eq!(report.duplicated_effort, 34);
+
+        let most_redundant: Vec<(usize, usize, u64)> = report
+            .most_redundant
+            .iter()
+            .map(|redundancy| {
+                (
+                    redundancy.assignment.line_number,
+                    redundancy.assignment.elf,
+                    redundancy.shared_sections,
+                )
+            })
+            .collect();
+        assert_eq!(most_redundant, [(4, 0, 7), (4, 1, 5), (6, 0, 5)]);
+    }
+
+    #[test]
+    fn it_merges_crowded_sections_with_different_counts() {
+        let assignments = get_assignments_from_file("./test.txt");
+        let report = get_coverage_report(&assignments, 3, 0);
+
+        assert_eq!(to_text(&report.crowded), ["2-8"]);
+        assert_eq!(report.most_redundant, []);
+    }
+}
@@ -0,0 +1,205 @@
+use crate::interval::{Interval, ParseIntervalError};
+use std::fmt;
+
+// An elf's assignment as one or more ranges, kept in order with gaps between them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Elf {
+    ranges: Vec<Interval<i32>>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseGroupError {
+    NoRanges,
+    InvalidRange(ParseIntervalError),
+    EmptyRange(String),
+    OverlappingRanges(String),
+}
+
+impl fmt::Display for ParseGroupError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseGroupError::NoRanges => write!(f, "every elf needs at least one range"),
+            ParseGroupError::InvalidRange(error) => write!(f, "{}", error),
+            ParseGroupError::EmptyRange(text) => write!(f, "{:?} has no sections", text),
+            ParseGroupError::OverlappingRanges(text) => {
+                write!(f, "{:?} has ranges that overlap", text)
+            }
+        }
+    }
+}
+
+impl Elf {
+    // Ranges that touch or overlap are merged, so every range another elf fits in is a single one
+    pub fn new(mut ranges: Vec<Interval<i32>>) -> Elf {
+        ranges.sort_unstable_by_key(|range| range.start());
+        let mut merged: Vec<Interval<i32>> = Vec::with_capacity(ranges.len());
+        for range in ranges {
+            match merged.last().and_then(|last| last.union(&range)) {
+                Some(union) => *merged.last_mut().unwrap() = union,
+                None => merged.push(range),
+            }
+        }
+        Elf { ranges: merged }
+    }
+
+    // Every range of the other elf has to fit inside one of this elf's ranges. As both are in
+    // order, one pass over each is enough
+    pub fn contains(&self, other: &Elf) -> bool {
+        let mut index = 0;
+        other.ranges.iter().all(|range| {
+            while index < self.ranges.len() && self.ranges[index].exclusive_end() <= range.start() {
+                index += 1;
+            }
+            index < self.ranges.len() && self.ranges[index].contains_interval(range)
+        })
+    }
+
+    pub fn overlaps(&self, other: &Elf) -> bool {
+        let (mut first, mut second) = (0, 0);
+        while first < self.ranges.len() && second < other.ranges.len() {
+            if self.ranges[first].intersects(&other.ranges[second]) {
+                return true;
+            }
+            if self.ranges[first].exclusive_end() <= other.ranges[second].exclusive_end() {
+                first += 1;
+            } else {
+                second += 1;
+            }
+        }
+        false
+    }
+}
+
+impl fmt::Display for Elf {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let ranges: Vec<String> = self.ranges.iter().map(Interval::to_string).collect();
+        write!(f, "{}", ranges.join(";"))
+    }
+}
+
+fn parse_elf(text: &str) -> Result<Elf, ParseGroupError> {
+    if text.trim().is_empty() {
+        return Err(ParseGroupError::NoRanges);
+    }
+
+    let mut ranges = text
+        .split(';')
+        .map(|range| {
+            let interval: Interval<i32> = range.parse().map_err(ParseGroupError::InvalidRange)?;
+            if interval.is_empty() {
+                return Err(ParseGroupError::EmptyRange(range.to_string()));
+            }
+            Ok(interval)
+        })
+        .collect::<Result<Vec<Interval<i32>>, ParseGroupError>>()?;
+
+    ranges.sort_unstable_by_key(|range| range.start());
+    if ranges
+        .windows(2)
+        .any(|ranges| ranges[0].intersects(&ranges[1]))
+    {
+        return Err(ParseGroupError::OverlappingRanges(text.to_string()));
+    }
+    Ok(Elf::new(ranges))
+}
+
+// Elves are separated by commas and an elf's ranges by semicolons, so the original two-range
+// lines are groups of two elves with one range each
+pub fn parse_group(line: &str) -> Result<Vec<Elf>, ParseGroupError> {
+    line.split(',').map(parse_elf).collect()
+}
+
+pub fn has_contained_elf(group: &[Elf]) -> bool {
+    group.iter().enumerate().any(|(index, elf)| {
+        group
+            .iter()
+            .enumerate()
+            .any(|(other_index, other)| index != other_index && elf.contains(other))
+    })
+}
+
+pub fn has_overlapping_elves(group: &[Elf]) -> bool {
+    group
+        .iter()
+        .enumerate()
+        .any(|(index, elf)| group[index + 1..].iter().any(|other| elf.overlaps(other)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_elves(texts: &[&str]) -> Vec<Elf> {
+        texts.iter().map(|text| parse_elf(text).unwrap()).collect()
+    }
+
+    #[test]
+    fn it_parses_groups_in_both_formats() {
+        let group = parse_group("2-4;8-9,3-7,1-1").unwrap();
+        assert_eq!(
+            group,
+            [
+                Elf::new(vec![Interval::closed(2, 4), Interval::closed(8, 9)]),
+                Elf::new(vec![Interval::closed(3, 7)]),
+                Elf::new(vec![Interval::closed(1, 1)]),
+            ]
+        );
+        assert_eq!(group[0].to_string(), "2-4;8-9");
+
+        assert_eq!(parse_group("2-4,6-8").unwrap().len(), 2);
+        assert_eq!(parse_group("8-9;2-4").unwrap()[0].to_string(), "2-4;8-9");
+        assert_eq!(parse_group("4-6;1-3;7-7").unwrap()[0].to_string(), "1-7");
+    }
+
+    #[test]
+    fn it_rejects_malformed_groups() {
+        assert_eq!(parse_group("2-4,"), Err(ParseGroupError::NoRanges));
+        assert_eq!(
+            parse_group("2-4;x-5"),
+            Err(ParseGroupError::InvalidRange(
+                ParseIntervalError::InvalidStart("x".to_string())
+            ))
+        );
+        assert_eq!(
+            parse_group("5-3"),
+            Err(ParseGroupError::EmptyRange("5-3".to_string()))
+        );
+        assert_eq!(
+            parse_group("1-5;4-6,7-7"),
+            Err(ParseGroupError::OverlappingRanges("1-5;4-6".to_string()))
+        );
+    }
+
+    #[test]
+    fn it_compares_elves_with_several_ranges() {
+        let elves = parse_elves(&["1-3;7-9", "2-2;8-8", "1-2;5-6", "3-4", "7-8;10-10"]);
+
+        assert!(elves[0].contains(&elves[1]));
+        assert!(!elves[1].contains(&elves[0]));
+        assert!(!elves[0].contains(&elves[4]));
+        assert!(elves[0].contains(&elves[0]));
+
+        // Ranges that touch are one run of sections, so a range across them still fits
+        let touching = parse_elves(&["1-3;4-6", "2-5"]);
+        assert!(touching[0].contains(&touching[1]));
+        assert!(!touching[1].contains(&touching[0]));
+
+        assert!(elves[0].overlaps(&elves[1]));
+        assert!(elves[0].overlaps(&elves[4]));
+        assert!(!elves[2].overlaps(&elves[3]));
+        assert!(!elves[3].overlaps(&elves[4]));
+    }
+
+    #[test]
+    fn it_checks_every_pair_in_a_group() {
+        assert!(has_contained_elf(&parse_elves(&["3-4", "1-2;5-6", "3-3"])));
+        assert!(!has_contained_elf(&parse_elves(&["1-2;5-6", "3-4", "7-8"])));
+        assert!(!has_contained_elf(&parse_elves(&["1-1"])));
+
+        assert!(has_overlapping_elves(&parse_elves(&["1-2", "3-4", "4-9"])));
+        assert!(!has_overlapping_elves(&parse_elves(&[
+            "1-2;5-6", "3-4", "7-8"
+        ])));
+        assert!(!has_overlapping_elves(&parse_elves(&["1-1"])));
+    }
+}
@@ -0,0 +1,255 @@
+use crate::coverage::Assignment;
+use crate::interval::Interval;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Containment {
+    pub container: Assignment,
+    // One of the assignments the container fully contains, there may be others
+    pub contained: Assignment,
+}
+
+// An interval tree laid out in an array: the assignments are sorted by start, each subrange's
+// middle assignment is its root, and every root keeps the furthest end in its subrange so
+// queries can skip subranges that finish too early
+pub struct IntervalIndex {
+    assignments: Vec<Assignment>,
+    furthest_ends: Vec<i32>,
+}
+
+fn get_sort_key(assignment: &Assignment) -> (i32, i32, usize, usize) {
+    (
+        assignment.sections.start(),
+        assignment.sections.exclusive_end(),
+        assignment.line_number,
+        assignment.elf,
+    )
+}
+
+impl IntervalIndex {
+    // Empty assignments cover nothing, so they're left out of the index
+    pub fn new(assignments: &[Assignment]) -> IntervalIndex {
+        let mut assignments: Vec<Assignment> = assignments
+            .iter()
+            .copied()
+            .filter(|assignment| !assignment.sections.is_empty())
+            .collect();
+        assignments.sort_unstable_by_key(get_sort_key);
+
+        let mut index = IntervalIndex {
+            furthest_ends: vec![0; assignments.len()],
+            assignments,
+        };
+        index.set_furthest_ends(0, index.assignments.len());
+        index
+    }
+
+    fn set_furthest_ends(&mut self, low: usize, high: usize) -> i32 {
+        if low >= high {
+            return i32::MIN;
+        }
+        let middle = low + (high - low) / 2;
+        let furthest_end = self.assignments[middle]
+            .sections
+            .exclusive_end()
+            .max(self.set_furthest_ends(low, middle))
+            .max(self.set_furthest_ends(middle + 1, high));
+        self.furthest_ends[middle] = furthest_end;
+        furthest_end
+    }
+
+    fn collect_overlapping(
+        &self,
+        sections: &Interval<i32>,
+        low: usize,
+        high: usize,
+        overlapping: &mut Vec<Assignment>,
+    ) {
+        if low >= high {
+            return;
+        }
+        let middle = low + (high - low) / 2;
+        if self.furthest_ends[middle] <= sections.start() {
+            return;
+        }
+
+        self.collect_overlapping(sections, low, middle, overlapping);
+
+        // Everything from here on starts at or after the middle assignment
+        let assignment = &self.assignments[middle];
+        if assignment.sections.start() >= sections.exclusive_end() {
+            return;
+        }
+        if assignment.sections.intersects(sections) {
+            overlapping.push(*assignment);
+        }
+        self.collect_overlapping(sections, middle + 1, high, overlapping);
+    }
+
+    // The results come back in order of their start. A subrange is only skipped when it finishes
+    // too early or starts too late, and one can pass both checks without holding a result, so
+    // k results take O((k + 1) log n) at worst and never more than O(n)
+    pub fn get_overlapping(&self, sections: &Interval<i32>) -> Vec<Assignment> {
+        let mut overlapping: Vec<Assignment> = Vec::new();
+        if !sections.is_empty() {
+            self.collect_overlapping(sections, 0, self.assignments.len(), &mut overlapping);
+        }
+        overlapping
+    }
+
+    // Walks from the latest start back, keeping the earliest ending assignment seen so far, as
+    // that's the one most likely to fit inside the next assignment
+    pub fn get_containers(&self) -> Vec<Containment> {
+        let mut containers: Vec<Containment> = Vec::new();
+        let mut earliest_end: Option<&Assignment> = None;
+
+        // Assignments with the same start are sorted by end, so the first of them ends earliest
+        let groups = self
+            .assignments
+            .chunk_by(|first, second| first.sections.start() == second.sections.start());
+        for group in groups.rev() {
+            for (position, assignment) in group.iter().enumerate() {
+                // Another assignment with the same start that ends no later
+                let in_group = if position > 0 {
+                    Some(&group[0])
+                } else {
+                    group.get(1).filter(|other| {
+                        other.sections.exclusive_end() == assignment.sections.exclusive_end()
+                    })
+                };
+                let later_start = earliest_end.filter(|other| {
+                    other.sections.exclusive_end() <= assignment.sections.exclusive_end()
+                });
+
+                if let Some(contained) = in_group.or(later_start) {
+                    containers.push(Containment {
+                        container: *assignment,
+                        contained: *contained,
+                    });
+                }
+            }
+
+            if earliest_end.is_none_or(|other| {
+                group[0].sections.exclusive_end() <= other.sections.exclusive_end()
+            }) {
+                earliest_end = Some(&group[0]);
+            }
+        }
+
+        containers.sort_unstable_by_key(|containment| get_sort_key(&containment.container));
+        containers
+    }
+
+    // Counts every pair of overlapping assignments in O(n log n). Sorted by start, assignment i
+    // overlaps each earlier one that hasn't ended by the time it starts
+    pub fn count_overlapping_pairs(&self) -> u64 {
+        let mut ends: Vec<i32> = self
+            .assignments
+            .iter()
+            .map(|assignment| assignment.sections.exclusive_end())
+            .collect();
+        ends.sort_unstable();
+
+        let mut ended = 0;
+        self.assignments
+            .iter()
+            .enumerate()
+            .map(|(index, assignment)| {
+                while ended < ends.len() && ends[ended] <= assignment.sections.start() {
+                    ended += 1;
+                }
+                (index - ended) as u64
+            })
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::get_assignments_from_file;
+
+    fn get_sections(assignments: &[Assignment]) -> Vec<String> {
+        assignments
+            .iter()
+            .map(|assignment| assignment.sections.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn it_answers_queries_for_test_file() {
+        let index = IntervalIndex::new(&get_assignments_from_file("./test.txt"));
+
+        assert_eq!(
+            get_sections(&index.get_overlapping(&Interval::closed(4, 5))),
+            ["2-4", "2-6", "2-8", "3-7", "4-5", "4-6", "4-8", "5-7"]
+        );
+        assert_eq!(index.get_overlapping(&Interval::closed(10, 20)), []);
+
+        let containers: Vec<Assignment> = index
+            .get_containers()
+            .iter()
+            .map(|containment| containment.container)
+            .collect();
+        assert_eq!(
+            get_sections(&containers),
+            ["2-4", "2-6", "2-8", "3-7", "4-6", "4-8", "5-7", "6-8"]
+        );
+
+        assert_eq!(index.count_overlapping_pairs(), 49);
+    }
+
+    #[test]
+    fn it_matches_checking_every_pair_for_input_file() {
+        let assignments = get_assignments_from_file("./input.txt");
+        let index = IntervalIndex::new(&assignments);
+
+        let mut overlapping_pairs = 0;
+        let mut containers: Vec<Assignment> = Vec::new();
+        for (position, first) in assignments.iter().enumerate() {
+            let mut is_container = false;
+            for (other_position, second) in assignments.iter().enumerate() {
+                if position == other_position {
+                    continue;
+                }
+                if position < other_position && first.sections.intersects(&second.sections) {
+                    overlapping_pairs += 1;
+                }
+                is_container |= first.sections.contains_interval(&second.sections);
+            }
+            if is_container {
+                containers.push(*first);
+            }
+        }
+
+        assert_eq!(index.count_overlapping_pairs(), overlapping_pairs);
+
+        containers.sort_unstable_by_key(get_sort_key);
+        let found = index.get_containers();
+        assert_eq!(
+            found
+                .iter()
+                .map(|containment| containment.container)
+                .collect::<Vec<Assignment>>(),
+            containers
+        );
+        assert!(found.iter().all(|containment| containment
+            .container
+            .sections
+            .contains_interval(&containment.contained.sections)
+            && containment.container != containment.contained));
+
+        for query in [
+            Interval::closed(1, 1),
+            Interval::closed(40, 45),
+            Interval::closed(99, 120),
+        ] {
+            let expected: Vec<Assignment> = index
+                .assignments
+                .iter()
+                .copied()
+                .filter(|assignment| assignment.sections.intersects(&query))
+                .collect();
+            assert_eq!(index.get_overlapping(&query), expected);
+        }
+    }
+}
@@ -0,0 +1,430 @@
+use std::fmt;
+use std::ops::{Add, Sub};
+use std::str::FromStr;
+
+pub trait Endpoint:
+    Copy + Ord + Add<Output = Self> + Sub<Output = Self> + fmt::Display + FromStr
+{
+    const ONE: Self;
+
+    fn checked_add(self, other: Self) -> Option<Self>;
+
+    // How far it is up to other, which can't overflow even across the whole range of the type
+    fn distance_to(self, other: Self) -> u64;
+}
+
+macro_rules! impl_endpoint {
+    ($($type:ty),*) => {
+        $(
+            impl Endpoint for $type {
+                const ONE: Self = 1;
+
+                fn checked_add(self, other: Self) -> Option<Self> {
+                    <$type>::checked_add(self, other)
+                }
+
+                fn distance_to(self, other: Self) -> u64 {
+                    (other as i128 - self as i128) as u64
+                }
+            }
+        )*
+    };
+}
+
+impl_endpoint!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Kind {
+    // a-b includes both a and b, like the puzzle's section assignments
+    Closed,
+    // a-b includes a but not b
+    HalfOpen,
+}
+
+// Stored as [start, end) whatever the kind, the kind only decides how the end is read and written
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Interval<T> {
+    start: T,
+    end: T,
+    kind: Kind,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseIntervalError {
+    MissingDash(String),
+    InvalidStart(String),
+    InvalidEnd(String),
+    // A closed interval is kept as [start, end + 1), so it can't end at the largest value
+    EndTooLarge(String),
+}
+
+impl fmt::Display for ParseIntervalError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseIntervalError::MissingDash(text) => {
+                write!(f, "{:?} isn't of the form a-b", text)
+            }
+            ParseIntervalError::InvalidStart(text) => write!(f, "{:?} isn't a valid start", text),
+            ParseIntervalError::InvalidEnd(text) => write!(f, "{:?} isn't a valid end", text),
+            ParseIntervalError::EndTooLarge(text) => {
+                write!(f, "{:?} is too large to end a closed interval", text)
+            }
+        }
+    }
+}
+
+impl<T: Endpoint> Interval<T> {
+    // Panics if end is the largest value, use try_closed when that can happen
+    pub fn closed(start: T, end: T) -> Interval<T> {
+        Interval::try_closed(start, end).expect("A closed interval can't end at the largest value")
+    }
+
+    pub fn try_closed(start: T, end: T) -> Option<Interval<T>> {
+        Some(Interval {
+            start,
+            end: end.checked_add(T::ONE)?,
+            kind: Kind::Closed,
+        })
+    }
+
+    pub fn half_open(start: T, end: T) -> Interval<T> {
+        Interval {
+            start,
+            end,
+            kind: Kind::HalfOpen,
+        }
+    }
+
+    pub fn parse(text: &str, kind: Kind) -> Result<Interval<T>, ParseIntervalError> {
+        // Skip the first character when looking for the dash so a negative start still parses
+        let dash = text
+            .char_indices()
+            .skip(1)
+            .find(|(_, character)| *character == '-')
+            .map(|(index, _)| index)
+            .ok_or_else(|| ParseIntervalError::MissingDash(text.to_string()))?;
+        let (start, end) = (&text[..dash], &text[dash + 1..]);
+
+        let start: T = start
+            .trim()
+            .parse()
+            .map_err(|_| ParseIntervalError::InvalidStart(start.to_string()))?;
+        let end_value: T = end
+            .trim()
+            .parse()
+            .map_err(|_| ParseIntervalError::InvalidEnd(end.to_string()))?;
+
+        match kind {
+            Kind::Closed => Interval::try_closed(start, end_value)
+                .ok_or_else(|| ParseIntervalError::EndTooLarge(end.to_string())),
+            Kind::HalfOpen => Ok(Interval::half_open(start, end_value)),
+        }
+    }
+
+    fn with_bounds(&self, start: T, end: T) -> Interval<T> {
+        Interval {
+            start,
+            end,
+            kind: self.kind,
+        }
+    }
+
+    pub fn start(&self) -> T {
+        self.start
+    }
+
+    // The end as it's written for the interval's kind, so inclusive for closed intervals
+    pub fn end(&self) -> T {
+        match self.kind {
+            Kind::Closed => self.end - T::ONE,
+            Kind::HalfOpen => self.end,
+        }
+    }
+
+    // The first value after the interval, whatever its kind
+    pub fn exclusive_end(&self) -> T {
+        self.end
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.end <= self.start
+    }
+
+    // As a u64, so even an interval over every value of the type has a length
+    pub fn len(&self) -> u64 {
+        if self.is_empty() {
+            0
+        } else {
+            self.start.distance_to(self.end)
+        }
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.start <= value && value < self.end
+    }
+
+    // Every interval contains the empty interval
+    pub fn contains_interval(&self, other: &Interval<T>) -> bool {
+        other.is_empty() || (self.start <= other.start && other.end <= self.end)
+    }
+
+    pub fn intersects(&self, other: &Interval<T>) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    // The results below keep the kind of self, and are None instead of an empty interval
+    pub fn intersection(&self, other: &Interval<T>) -> Option<Interval<T>> {
+        let intersection = self.with_bounds(self.start.max(other.start), self.end.min(other.end));
+        (!intersection.is_empty()).then_some(intersection)
+    }
+
+    // None when the intervals neither overlap nor touch, as the union wouldn't be one interval
+    pub fn union(&self, other: &Interval<T>) -> Option<Interval<T>> {
+        match (self.is_empty(), other.is_empty()) {
+            (true, true) => None,
+            (false, true) => Some(*self),
+            (true, false) => Some(self.with_bounds(other.start, other.end)),
+            (false, false) => (self.start <= other.end && other.start <= self.end)
+                .then(|| self.with_bounds(self.start.min(other.start), self.end.max(other.end))),
+        }
+    }
+
+    // The parts of self not in other, in order, there can be up to two
+    pub fn difference(&self, other: &Interval<T>) -> Vec<Interval<T>> {
+        let Some(intersection) = self.intersection(other) else {
+            return if self.is_empty() { vec![] } else { vec![*self] };
+        };
+
+        [
+            self.with_bounds(self.start, intersection.start),
+            self.with_bounds(intersection.end, self.end),
+        ]
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .collect()
+    }
+}
+
+impl<T: Endpoint> fmt::Display for Interval<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}-{}", self.start(), self.end())
+    }
+}
+
+// Without a kind, the puzzle's a-b syntax is read as a closed interval
+impl<T: Endpoint> FromStr for Interval<T> {
+    type Err = ParseIntervalError;
+
+    fn from_str(text: &str) -> Result<Interval<T>, ParseIntervalError> {
+        Interval::parse(text, Kind::Closed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    const DOMAIN: i32 = 8;
+
+    // Every interval, empty ones included, that has both bounds in a small domain
+    fn get_small_intervals() -> Vec<Interval<i32>> {
+        let mut intervals = Vec::new();
+        for start in 0..DOMAIN {
+            for end in 0..DOMAIN {
+                intervals.push(Interval::half_open(start, end));
+                intervals.push(Interval::closed(start, end));
+            }
+        }
+        intervals
+    }
+
+    // The interval as a set of values, which the operations are checked against
+    fn to_bits(interval: &Interval<i32>) -> u32 {
+        (0..=DOMAIN)
+            .filter(|value| interval.contains(*value))
+            .fold(0, |bits, value| bits | 1 << value)
+    }
+
+    fn to_bits_of_all(intervals: &[Interval<i32>]) -> u32 {
+        intervals
+            .iter()
+            .fold(0, |bits, interval| bits | to_bits(interval))
+    }
+
+    // Whether the set bits form a single run, so the set can be written as one interval
+    fn is_one_run(bits: u32) -> bool {
+        let shifted = bits >> bits.trailing_zeros();
+        shifted & (shifted + 1) == 0
+    }
+
+    #[test]
+    fn it_keeps_closed_and_half_open_bounds_apart() {
+        let closed = Interval::closed(2, 4);
+        assert_eq!((closed.start(), closed.end(), closed.len()), (2, 4, 3));
+        assert!(closed.contains(4));
+        assert!(!closed.contains(5));
+
+        let half_open = Interval::half_open(2, 4);
+        assert_eq!(
+            (half_open.start(), half_open.end(), half_open.len()),
+            (2, 4, 2)
+        );
+        assert!(half_open.contains(3));
+        assert!(!half_open.contains(4));
+
+        assert!(Interval::closed(3, 2).is_empty());
+        assert!(Interval::half_open(3, 3).is_empty());
+        assert_eq!(Interval::closed(7, 1).len(), 0);
+        assert_eq!(Interval::<u8>::closed(0, 0).len(), 1);
+
+        assert_eq!(
+            Interval::half_open(i32::MIN, i32::MAX).len(),
+            u32::MAX as u64
+        );
+        assert_eq!(Interval::closed(i64::MIN, i64::MAX - 1).len(), u64::MAX);
+        assert_eq!(Interval::half_open(0, u64::MAX).len(), u64::MAX);
+    }
+
+    #[test]
+    fn it_parses_and_formats_dashed_ranges() {
+        let interval: Interval<i32> = "2-4".parse().unwrap();
+        assert_eq!(interval, Interval::closed(2, 4));
+        assert_eq!(interval.to_string(), "2-4");
+
+        let interval: Interval<i64> = Interval::parse("-5--1", Kind::HalfOpen).unwrap();
+        assert_eq!(interval, Interval::half_open(-5, -1));
+        assert_eq!(interval.to_string(), "-5--1");
+
+        assert_eq!(
+            "24".parse::<Interval<i32>>(),
+            Err(ParseIntervalError::MissingDash("24".to_string()))
+        );
+        assert_eq!(
+            "x-4".parse::<Interval<i32>>(),
+            Err(ParseIntervalError::InvalidStart("x".to_string()))
+        );
+        assert_eq!(
+            "2-".parse::<Interval<i32>>(),
+            Err(ParseIntervalError::InvalidEnd("".to_string()))
+        );
+        assert_eq!(
+            "1-300".parse::<Interval<u8>>(),
+            Err(ParseIntervalError::InvalidEnd("300".to_string()))
+        );
+        assert_eq!(
+            "0-255".parse::<Interval<u8>>(),
+            Err(ParseIntervalError::EndTooLarge("255".to_string()))
+        );
+        assert_eq!(
+            "0-254".parse::<Interval<u8>>(),
+            Ok(Interval::closed(0, 254))
+        );
+        assert_eq!(
+            Interval::parse("0-255", Kind::HalfOpen),
+            Ok(Interval::<u8>::half_open(0, 255))
+        );
+        assert_eq!(Interval::try_closed(0, i32::MAX), None);
+    }
+
+    #[test]
+    fn it_matches_set_semantics_for_every_small_interval() {
+        let intervals = get_small_intervals();
+
+        for first in &intervals {
+            let first_bits = to_bits(first);
+            assert_eq!(first.len() as u32, first_bits.count_ones());
+            assert_eq!(first.is_empty(), first_bits == 0);
+
+            for second in &intervals {
+                let second_bits = to_bits(second);
+
+                assert_eq!(
+                    first.contains_interval(second),
+                    second_bits & !first_bits == 0
+                );
+                assert_eq!(first.intersects(second), first_bits & second_bits != 0);
+
+                let intersection = first.intersection(second);
+                assert_eq!(
+                    intersection.as_ref().map_or(0, to_bits),
+                    first_bits & second_bits
+                );
+                assert!(intersection.is_none_or(|interval| interval.kind == first.kind));
+
+                let union = first.union(second);
+                let union_bits = first_bits | second_bits;
+                assert_eq!(union.is_some(), union_bits != 0 && is_one_run(union_bits));
+                if let Some(union) = union {
+                    assert_eq!(to_bits(&union), union_bits);
+                }
+
+                let difference = first.difference(second);
+                assert_eq!(to_bits_of_all(&difference), first_bits & !second_bits);
+                assert!(difference.iter().all(|part| !part.is_empty()));
+                assert!(difference
+                    .windows(2)
+                    .all(|parts| parts[0].end <= parts[1].start));
+            }
+        }
+    }
+
+    fn any_interval() -> impl Strategy<Value = Interval<i64>> {
+        (
+            -1_000_000i64..1_000_000,
+            -1_000_000i64..1_000_000,
+            any::<bool>(),
+        )
+            .prop_map(|(start, end, is_closed)| {
+                if is_closed {
+                    Interval::closed(start, end)
+                } else {
+                    Interval::half_open(start, end)
+                }
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn it_round_trips_through_its_text(interval in any_interval()) {
+            prop_assert_eq!(Interval::parse(&interval.to_string(), interval.kind), Ok(interval));
+        }
+
+        #[test]
+        fn it_keeps_lengths_consistent(first in any_interval(), second in any_interval()) {
+            let intersection_length = first.intersection(&second).map_or(0, |interval| interval.len());
+
+            if let Some(union) = first.union(&second) {
+                prop_assert_eq!(union.len() + intersection_length, first.len() + second.len());
+            }
+
+            let difference_length: u64 = first.difference(&second).iter().map(Interval::len).sum();
+            prop_assert_eq!(difference_length + intersection_length, first.len());
+        }
+
+        #[test]
+        fn it_keeps_values_consistent(
+            first in any_interval(),
+            second in any_interval(),
+            value in -1_000_001i64..1_000_001,
+        ) {
+            let in_intersection = first.intersection(&second).is_some_and(|interval| interval.contains(value));
+            prop_assert_eq!(in_intersection, first.contains(value) && second.contains(value));
+
+            let in_difference = first.difference(&second).iter().any(|part| part.contains(value));
+            prop_assert_eq!(in_difference, first.contains(value) && !second.contains(value));
+
+            if let Some(union) = first.union(&second) {
+                prop_assert_eq!(union.contains(value), first.contains(value) || second.contains(value));
+            }
+        }
+
+        #[test]
+        fn it_agrees_on_containment_and_intersection(first in any_interval(), second in any_interval()) {
+            prop_assert_eq!(first.intersects(&second), second.intersects(&first));
+            if first.contains_interval(&second) && !second.is_empty() {
+                prop_assert_eq!(first.intersection(&second).map(|interval| interval.len()), Some(second.len()));
+            }
+        }
+    }
+}
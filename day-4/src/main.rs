@@ -1,82 +1,293 @@
-use std::{cmp, fs};
+mod bench;
+mod coverage;
+mod group;
+mod index;
+mod interval;
+mod reassignment;
 
-fn are_ranges_fully_overlapping(first_range: (i32, i32), second_range: (i32, i32)) -> bool {
-    let start_of_overlap = cmp::max(first_range.0, second_range.0);
-    let end_of_overlap = cmp::min(first_range.1, second_range.1);
+use coverage::{get_coverage_report, Assignment};
+use group::{has_contained_elf, has_overlapping_elves, parse_group, Elf};
+use index::IntervalIndex;
+use interval::Interval;
+use reassignment::{reassign, reassign_pairs, to_lines};
+use std::{env, fs};
 
-    return (start_of_overlap, end_of_overlap) == first_range
-        || (start_of_overlap, end_of_overlap) == second_range;
+// One range is fully inside the other when taking the other away leaves nothing of it
+fn are_ranges_fully_overlapping(first_range: Interval<i32>, second_range: Interval<i32>) -> bool {
+    first_range.difference(&second_range).is_empty()
+        || second_range.difference(&first_range).is_empty()
 }
 
-fn are_ranges_overlapping(first_range: (i32, i32), second_range: (i32, i32)) -> bool {
-    return cmp::max(first_range.0, second_range.0) <= cmp::min(first_range.1, second_range.1);
+// Whichever range starts later starts inside the other when they overlap
+fn are_ranges_overlapping(first_range: Interval<i32>, second_range: Interval<i32>) -> bool {
+    let starts_inside = |range: Interval<i32>, other: Interval<i32>| {
+        !range.is_empty() && other.contains(range.start())
+    };
+    starts_inside(first_range, second_range) || starts_inside(second_range, first_range)
 }
 
-fn convert_dashed_range_to_tuple(dashed_range: &str) -> (i32, i32) {
-    let number_range = dashed_range.split("-").collect::<Vec<&str>>();
+// A line can have a single range when the reassignment left the other elf without work, and
+// then there's nothing on it to overlap
+fn get_ranges_from_file(file_path: &str) -> Vec<Vec<Interval<i32>>> {
+    let file_contents =
+        fs::read_to_string(file_path).expect("Should have been able to read the file");
 
-    let mut tuple = (0, 0);
+    file_contents
+        .lines()
+        .map(|line| match line.split_once(',') {
+            Some((first_range, second_range)) => {
+                vec![first_range.parse().unwrap(), second_range.parse().unwrap()]
+            }
+            None => vec![line.parse().expect("Should have one or two ranges")],
+        })
+        .collect()
+}
 
-    if let [first_number, second_number] = &number_range[..] {
-        tuple.0 = first_number.parse().unwrap();
-        tuple.1 = second_number.parse().unwrap();
-    }
+fn get_number_of_overlaps(
+    file_path: &str,
+    are_overlaps: &dyn Fn(Interval<i32>, Interval<i32>) -> bool,
+) -> i32 {
+    let ranges = get_ranges_from_file(file_path);
 
-    return tuple;
+    ranges
+        .iter()
+        .filter(|ranges| matches!(ranges[..], [first, second] if are_overlaps(first, second)))
+        .count() as i32
 }
 
-fn get_ranges_from_file(file_path: &str) -> Vec<((i32, i32), (i32, i32))> {
+fn get_groups_from_file(file_path: &str) -> Vec<Vec<Elf>> {
     let file_contents =
         fs::read_to_string(file_path).expect("Should have been able to read the file");
 
-    let file_contents_by_line = file_contents.lines();
+    file_contents
+        .lines()
+        .enumerate()
+        .map(|(index, line)| {
+            parse_group(line).unwrap_or_else(|error| panic!("Line {}: {}", index + 1, error))
+        })
+        .collect()
+}
 
-    let mut ranges: Vec<((i32, i32), (i32, i32))> = Vec::new();
+fn get_number_of_groups(file_path: &str, is_counted: &dyn Fn(&[Elf]) -> bool) -> i32 {
+    let groups = get_groups_from_file(file_path);
 
-    for line in file_contents_by_line {
-        let string_ranges_for_line = line.split(",").collect::<Vec<&str>>();
+    groups.iter().filter(|group| is_counted(group)).count() as i32
+}
 
-        let first_tuple = convert_dashed_range_to_tuple(string_ranges_for_line[0]);
-        let second_tuple = convert_dashed_range_to_tuple(string_ranges_for_line[1]);
+fn get_assignments_from_file(file_path: &str) -> Vec<Assignment> {
+    get_ranges_from_file(file_path)
+        .iter()
+        .enumerate()
+        .flat_map(|(index, ranges)| {
+            ranges
+                .iter()
+                .enumerate()
+                .map(move |(elf, sections)| Assignment {
+                    line_number: index + 1,
+                    elf,
+                    sections: *sections,
+                })
+        })
+        .collect()
+}
 
-        ranges.push((first_tuple, second_tuple));
+fn to_text(intervals: &[Interval<i32>]) -> String {
+    if intervals.is_empty() {
+        return "none".to_string();
     }
+    intervals
+        .iter()
+        .map(Interval::to_string)
+        .collect::<Vec<String>>()
+        .join(", ")
+}
 
-    return ranges;
+fn print_coverage_report(file_path: &str, more_than: usize, top: usize) {
+    let assignments = get_assignments_from_file(file_path);
+    let report = get_coverage_report(&assignments, more_than, top);
+
+    println!("Camp sections: {}", report.camp);
+    println!("Uncovered sections: {}", to_text(&report.uncovered));
+    println!(
+        "Sections with more than {} elves: {}",
+        more_than,
+        to_text(&report.crowded)
+    );
+    println!(
+        "Most elves on one section: {}",
+        report.most_elves_on_a_section
+    );
+    println!(
+        "Duplicated effort: {} of {} assigned sections cover {} distinct sections",
+        report.duplicated_effort, report.assigned_sections, report.covered_sections
+    );
+    println!("Most redundant elves:");
+    for redundancy in &report.most_redundant {
+        println!(
+            "  Line {}, elf {} ({}): {} of {} sections also covered by others",
+            redundancy.assignment.line_number,
+            redundancy.assignment.elf + 1,
+            redundancy.assignment.sections,
+            redundancy.shared_sections,
+            redundancy.assignment.sections.len()
+        );
+    }
 }
 
-fn get_number_of_overlaps(
-    file_path: &str,
-    are_overlaps: &dyn Fn((i32, i32), (i32, i32)) -> bool,
-) -> i32 {
-    let ranges = get_ranges_from_file(file_path);
+fn print_group_counts(file_path: &str) {
+    let number_with_contained_elf = get_number_of_groups(file_path, &has_contained_elf);
+    println!(
+        "Groups with an elf fully contained by another: {}",
+        number_with_contained_elf
+    );
+    let number_with_overlapping_elves = get_number_of_groups(file_path, &has_overlapping_elves);
+    println!(
+        "Groups with two overlapping elves: {}",
+        number_with_overlapping_elves
+    );
+}
 
-    return ranges
-        .iter()
-        .map(|range| {
-            if are_overlaps(range.0, range.1) {
-                return 1;
-            }
-            return 0;
-        })
-        .sum();
+fn print_index_report(file_path: &str) {
+    let index = IntervalIndex::new(&get_assignments_from_file(file_path));
+
+    let overlapping_pairs = index.count_overlapping_pairs();
+    let overlapping_within_lines = get_number_of_overlaps(file_path, &are_ranges_overlapping);
+    println!("Overlapping pairs of elves: {}", overlapping_pairs);
+    println!(
+        "Overlapping pairs of elves on different lines: {}",
+        overlapping_pairs - overlapping_within_lines as u64
+    );
+
+    let containers = index.get_containers();
+    println!("Assignments fully containing another: {}", containers.len());
+    for containment in containers.iter().take(10) {
+        println!(
+            "  Line {}, elf {} ({}) contains line {}, elf {} ({})",
+            containment.container.line_number,
+            containment.container.elf + 1,
+            containment.container.sections,
+            containment.contained.line_number,
+            containment.contained.elf + 1,
+            containment.contained.sections
+        );
+    }
+    if containers.len() > 10 {
+        println!("  ...");
+    }
 }
 
-fn main() {
+fn print_overlapping(sections: &str, file_path: &str) {
+    let sections: Interval<i32> = sections
+        .parse()
+        .expect("Sections should be of the form a-b");
+    let index = IntervalIndex::new(&get_assignments_from_file(file_path));
+
+    let overlapping = index.get_overlapping(&sections);
+    println!(
+        "Assignments overlapping {}: {}",
+        sections,
+        overlapping.len()
+    );
+    for assignment in overlapping {
+        println!(
+            "  Line {}, elf {}: {}",
+            assignment.line_number,
+            assignment.elf + 1,
+            assignment.sections
+        );
+    }
+}
+
+fn print_part_counts(file_path: &str) {
     // part 1
-    let number_of_full_overlaps =
-        get_number_of_overlaps("./test.txt", &are_ranges_fully_overlapping);
+    let number_of_full_overlaps = get_number_of_overlaps(file_path, &are_ranges_fully_overlapping);
     println!("Part 1: {}", number_of_full_overlaps);
     // part 2
-    let number_of_overlaps = get_number_of_overlaps("./test.txt", &are_ranges_overlapping);
+    let number_of_overlaps = get_number_of_overlaps(file_path, &are_ranges_overlapping);
     println!("Part 2: {}", number_of_overlaps);
 }
 
+// The new assignments go to stdout so they can be fed straight back in with parts or
+// groups, the summary to stderr
+fn print_reassignment(file_path: &str, is_whole_camp: bool) {
+    let assignments = get_assignments_from_file(file_path);
+    let reassignment = if is_whole_camp {
+        reassign(&assignments)
+    } else {
+        reassign_pairs(&assignments)
+    };
+
+    for line in to_lines(&reassignment.assignments) {
+        println!("{}", line);
+    }
+    eprintln!(
+        "Changed sections: {}, elves left without work: {}",
+        reassignment.changed_sections, reassignment.elves_without_work
+    );
+}
+
+fn main() {
+    let arguments: Vec<String> = env::args().skip(1).collect();
+
+    match arguments.iter().map(String::as_str).collect::<Vec<&str>>()[..] {
+        // cargo run --release -- bench
+        ["bench"] => bench::run_benchmarks(),
+        // cargo run -- coverage [file] [more than] [top]
+        ["coverage"] => print_coverage_report("./input.txt", 1, 5),
+        ["coverage", file_path] => print_coverage_report(file_path, 1, 5),
+        ["coverage", file_path, more_than, top] => print_coverage_report(
+            file_path,
+            more_than.parse().expect("More than should be a number"),
+            top.parse().expect("Top should be a number"),
+        ),
+        // cargo run -- groups [file]
+        ["groups"] => print_group_counts("./input.txt"),
+        ["groups", file_path] => print_group_counts(file_path),
+        // cargo run -- index [file]
+        ["index"] => print_index_report("./input.txt"),
+        ["index", file_path] => print_index_report(file_path),
+        // cargo run -- overlapping <a-b> [file]
+        ["overlapping", sections] => print_overlapping(sections, "./input.txt"),
+        ["overlapping", sections, file_path] => print_overlapping(sections, file_path),
+        // cargo run -- reassign <pairs|camp> [file]
+        ["reassign", "pairs"] => print_reassignment("./input.txt", false),
+        ["reassign", "pairs", file_path] => print_reassignment(file_path, false),
+        ["reassign", "camp"] => print_reassignment("./input.txt", true),
+        ["reassign", "camp", file_path] => print_reassignment(file_path, true),
+        // cargo run -- parts <file>
+        ["parts", file_path] => print_part_counts(file_path),
+        _ => print_part_counts("./test.txt"),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::are_ranges_fully_overlapping;
     use crate::are_ranges_overlapping;
+    use crate::get_number_of_groups;
     use crate::get_number_of_overlaps;
+    use crate::group::{has_contained_elf, has_overlapping_elves};
+    use crate::interval::Interval;
+
+    #[test]
+    fn it_counts_overlaps_like_the_interval_operations() {
+        let ranges: Vec<Interval<i32>> = (0..6)
+            .flat_map(|start| (0..6).map(move |end| Interval::closed(start, end)))
+            .collect();
+
+        for first in &ranges {
+            for second in &ranges {
+                assert_eq!(
+                    are_ranges_fully_overlapping(*first, *second),
+                    first.contains_interval(second) || second.contains_interval(first)
+                );
+                assert_eq!(
+                    are_ranges_overlapping(*first, *second),
+                    first.intersects(second)
+                );
+            }
+        }
+    }
 
     #[test]
     fn it_returns_expected_number_of_full_overlaps_for_test_file() {
@@ -103,4 +314,30 @@ mod tests {
         let number_of_overlaps = get_number_of_overlaps("./input.txt", &are_ranges_overlapping);
         assert_eq!(number_of_overlaps, 815);
     }
+
+    #[test]
+    fn it_returns_same_counts_for_groups_in_the_original_format() {
+        assert_eq!(get_number_of_groups("./test.txt", &has_contained_elf), 2);
+        assert_eq!(get_number_of_groups("./input.txt", &has_contained_elf), 500);
+        assert_eq!(
+            get_number_of_groups("./test.txt", &has_overlapping_elves),
+            4
+        );
+        assert_eq!(
+            get_number_of_groups("./input.txt", &has_overlapping_elves),
+            815
+        );
+    }
+
+    #[test]
+    fn it_returns_expected_counts_for_groups_test_file() {
+        assert_eq!(
+            get_number_of_groups("./test-groups.txt", &has_contained_elf),
+            1
+        );
+        assert_eq!(
+            get_number_of_groups("./test-groups.txt", &has_overlapping_elves),
+            3
+        );
+    }
 }
@@ -0,0 +1,293 @@
+use crate::coverage::Assignment;
+use crate::interval::Interval;
+use std::collections::BTreeMap;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Reassignment {
+    // In the original order, leaving out each elf left without work
+    pub assignments: Vec<Assignment>,
+    pub changed_sections: u64,
+    pub elves_without_work: usize,
+}
+
+// Keyed by start, each piece is the exclusive end and the index of the assignment working it
+type Pieces = BTreeMap<i32, (i32, usize)>;
+
+// Covers the union with as few elves as possible, each taking over from where the last one
+// finished and carrying on to the end of its own assignment
+fn get_fewest_pieces(assignments: &[Assignment]) -> Pieces {
+    let mut order: Vec<usize> = (0..assignments.len())
+        .filter(|index| !assignments[*index].sections.is_empty())
+        .collect();
+    order.sort_unstable_by_key(|index| assignments[*index].sections.start());
+
+    let end = |index: usize| assignments[index].sections.exclusive_end();
+
+    let mut pieces = Pieces::new();
+    let mut covered_until = i32::MIN;
+    let mut next = 0;
+
+    while next < order.len() {
+        let position = covered_until.max(assignments[order[next]].sections.start());
+
+        // Whoever reaches furthest from here, anyone starting earlier already ends by now
+        let mut furthest: Option<usize> = None;
+        while next < order.len() && assignments[order[next]].sections.start() <= position {
+            if furthest.is_none_or(|furthest| end(order[next]) > end(furthest)) {
+                furthest = Some(order[next]);
+            }
+            next += 1;
+        }
+
+        if let Some(furthest) = furthest.filter(|furthest| end(*furthest) > position) {
+            pieces.insert(position, (end(furthest), furthest));
+            covered_until = end(furthest);
+        }
+    }
+
+    pieces
+}
+
+// An elf left without work can take the start or the end of a piece that's inside its own
+// assignment, as long as the piece's elf keeps some of it. That moves sections from one elf
+// to the other, so it doesn't change how many sections change hands
+fn share_pieces(assignments: &[Assignment], pieces: &mut Pieces) {
+    let mut is_working = vec![false; assignments.len()];
+    for (_, owner) in pieces.values() {
+        is_working[*owner] = true;
+    }
+
+    for (index, assignment) in assignments.iter().enumerate() {
+        if is_working[index] || assignment.sections.is_empty() {
+            continue;
+        }
+        let (start, end) = (
+            assignment.sections.start(),
+            assignment.sections.exclusive_end(),
+        );
+
+        let piece_at_start = pieces.range(..=start).next_back().map(|(&s, &p)| (s, p));
+        if let Some((piece_start, (piece_end, owner))) = piece_at_start {
+            if piece_start < start && start < piece_end && piece_end <= end {
+                pieces.insert(piece_start, (start, owner));
+                pieces.insert(start, (piece_end, index));
+                continue;
+            }
+        }
+
+        let piece_at_end = pieces.range(..end).next_back().map(|(&s, &p)| (s, p));
+        if let Some((piece_start, (piece_end, owner))) = piece_at_end {
+            if start <= piece_start && end < piece_end {
+                pieces.insert(piece_start, (end, index));
+                pieces.insert(end, (piece_end, owner));
+            }
+        }
+    }
+}
+
+// Finds new assignments that don't overlap and still cover every section that was covered,
+// changing as few sections as possible. Any way of doing that keeps each section with one elf
+// who already had it, so trimming alone always does as well as shifting could: the changes
+// come to exactly the duplicated effort. Among those, elves are kept working where it's free
+pub fn reassign(assignments: &[Assignment]) -> Reassignment {
+    let mut pieces = get_fewest_pieces(assignments);
+    share_pieces(assignments, &mut pieces);
+
+    let mut new_sections: Vec<Option<Interval<i32>>> = vec![None; assignments.len()];
+    for (start, (end, owner)) in &pieces {
+        new_sections[*owner] = Some(Interval::closed(*start, end - 1));
+    }
+
+    let new_assignments: Vec<Assignment> = assignments
+        .iter()
+        .zip(new_sections)
+        .filter_map(|(assignment, sections)| {
+            Some(Assignment {
+                sections: sections?,
+                ..*assignment
+            })
+        })
+        .collect();
+
+    let get_total = |assignments: &[Assignment]| -> u64 {
+        assignments
+            .iter()
+            .map(|assignment| assignment.sections.len())
+            .sum()
+    };
+
+    Reassignment {
+        changed_sections: get_total(assignments) - get_total(&new_assignments),
+        elves_without_work: assignments.len() - new_assignments.len(),
+        assignments: new_assignments,
+    }
+}
+
+// Only elves on the same line are compared, so each line is fixed on its own
+pub fn reassign_pairs(assignments: &[Assignment]) -> Reassignment {
+    let reassignments: Vec<Reassignment> = assignments
+        .chunk_by(|first, second| first.line_number == second.line_number)
+        .map(reassign)
+        .collect();
+
+    Reassignment {
+        changed_sections: reassignments
+            .iter()
+            .map(|reassignment| reassignment.changed_sections)
+            .sum(),
+        elves_without_work: reassignments
+            .iter()
+            .map(|reassignment| reassignment.elves_without_work)
+            .sum(),
+        assignments: reassignments
+            .into_iter()
+            .flat_map(|reassignment| reassignment.assignments)
+            .collect(),
+    }
+}
+
+// Back in the a-b,c-d format, one line per original line that still has an elf working, so
+// the output never holds an empty range
+pub fn to_lines(assignments: &[Assignment]) -> Vec<String> {
+    assignments
+        .chunk_by(|first, second| first.line_number == second.line_number)
+        .map(|line| {
+            line.iter()
+                .map(|assignment| assignment.sections.to_string())
+                .collect::<Vec<String>>()
+                .join(",")
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coverage::get_coverage_report;
+    use crate::group::{has_contained_elf, has_overlapping_elves};
+    use crate::index::IntervalIndex;
+    use crate::{
+        are_ranges_fully_overlapping, are_ranges_overlapping, get_assignments_from_file,
+        get_number_of_groups, get_number_of_overlaps, get_ranges_from_file,
+    };
+    use std::{env, fs, process};
+
+    fn to_assignments(ranges: &[(i32, i32)]) -> Vec<Assignment> {
+        ranges
+            .iter()
+            .enumerate()
+            .map(|(elf, (start, end))| Assignment {
+                line_number: 1,
+                elf,
+                sections: Interval::closed(*start, *end),
+            })
+            .collect()
+    }
+
+    fn assert_is_valid(before: &[Assignment], after: &Reassignment) {
+        assert!(after.assignments.iter().all(|after| {
+            !after.sections.is_empty()
+                && before.iter().any(|before| {
+                    (before.line_number, before.elf) == (after.line_number, after.elf)
+                        && before.sections.contains_interval(&after.sections)
+                })
+        }));
+        assert_eq!(
+            after.assignments.len() + after.elves_without_work,
+            before.len()
+        );
+        assert_eq!(
+            IntervalIndex::new(&after.assignments).count_overlapping_pairs(),
+            0
+        );
+
+        let before_report = get_coverage_report(before, 1, 0);
+        let after_report = get_coverage_report(&after.assignments, 1, 0);
+        assert_eq!(before_report.uncovered, after_report.uncovered);
+        assert_eq!(before_report.camp, after_report.camp);
+        assert_eq!(after.changed_sections, before_report.duplicated_effort);
+    }
+
+    #[test]
+    fn it_trims_pairs() {
+        let reassignment = reassign(&to_assignments(&[(2, 6), (4, 8)]));
+        assert_eq!(to_lines(&reassignment.assignments), ["2-6,7-8"]);
+        assert_eq!(reassignment.changed_sections, 3);
+
+        let reassignment = reassign(&to_assignments(&[(1, 10), (1, 2)]));
+        assert_eq!(to_lines(&reassignment.assignments), ["3-10,1-2"]);
+        assert_eq!(reassignment.elves_without_work, 0);
+
+        let reassignment = reassign(&to_assignments(&[(1, 10), (4, 5)]));
+        assert_eq!(to_lines(&reassignment.assignments), ["1-10"]);
+        assert_eq!(reassignment.changed_sections, 2);
+        assert_eq!(reassignment.elves_without_work, 1);
+
+        let reassignment = reassign(&to_assignments(&[(1, 3), (5, 7)]));
+        assert_eq!(to_lines(&reassignment.assignments), ["1-3,5-7"]);
+        assert_eq!(reassignment.changed_sections, 0);
+    }
+
+    #[test]
+    fn it_reassigns_pairs_for_input_file() {
+        let assignments = get_assignments_from_file("./input.txt");
+        let reassignment = reassign_pairs(&assignments);
+
+        // Every line keeps at least one elf, since its own elves covered it
+        for (before, after) in assignments.chunks(2).zip(
+            reassignment
+                .assignments
+                .chunk_by(|first, second| first.line_number == second.line_number),
+        ) {
+            let line = reassign(before);
+            assert_is_valid(before, &line);
+            assert_eq!(line.assignments, after);
+        }
+
+        assert_reads_back_without_overlaps(&reassignment, "pairs");
+    }
+
+    // Parts 1 and 2, and the groups mode, find nothing left to fix once the new lines are read
+    // back in
+    fn assert_reads_back_without_overlaps(reassignment: &Reassignment, name: &str) {
+        let file_path =
+            env::temp_dir().join(format!("day-4-reassign-{}-{}.txt", name, process::id()));
+        fs::write(&file_path, to_lines(&reassignment.assignments).join("\n"))
+            .expect("Should have been able to write the file");
+        let file_path = file_path.to_str().unwrap();
+
+        assert_eq!(
+            get_ranges_from_file(file_path).concat(),
+            reassignment
+                .assignments
+                .iter()
+                .map(|assignment| assignment.sections)
+                .collect::<Vec<Interval<i32>>>()
+        );
+        assert_eq!(
+            get_number_of_overlaps(file_path, &are_ranges_fully_overlapping),
+            0
+        );
+        assert_eq!(
+            get_number_of_overlaps(file_path, &are_ranges_overlapping),
+            0
+        );
+        assert_eq!(get_number_of_groups(file_path, &has_contained_elf), 0);
+        assert_eq!(get_number_of_groups(file_path, &has_overlapping_elves), 0);
+        fs::remove_file(file_path).expect("Should have been able to remove the file");
+    }
+
+    #[test]
+    fn it_reassigns_the_whole_camp() {
+        for file_path in ["./test.txt", "./input.txt"] {
+            let assignments = get_assignments_from_file(file_path);
+            assert_is_valid(&assignments, &reassign(&assignments));
+        }
+
+        let assignments = get_assignments_from_file("./test.txt");
+        let reassignment = reassign(&assignments);
+        assert_eq!(reassignment.changed_sections, 34);
+        assert_eq!(reassignment.elves_without_work, 4);
+        assert_reads_back_without_overlaps(&reassignment, "camp");
+    }
+}
@@ -0,0 +1,142 @@
+use crate::command::Move;
+use crate::crane::{Crane, CrateMover9000, CrateMover9001};
+use crate::random::Random;
+use std::time::Instant;
+
+const NUMBER_OF_STACKS: usize = 9;
+const NUMBER_OF_CRATES: usize = 100_000;
+const NUMBER_OF_MOVES: usize = 1_000_000;
+const LARGEST_MOVE: usize = 40;
+
+// Stacks bottom first, and moves that never ask for more crates than there are
+fn generate_stacks_and_moves(random: &mut Random) -> (Vec<Vec<char>>, Vec<Move>) {
+    let mut stacks: Vec<Vec<char>> = vec![Vec::new(); NUMBER_OF_STACKS];
+    for _ in 0..NUMBER_OF_CRATES {
+        stacks[random.below(NUMBER_OF_STACKS)].push((b'A' + random.below(26) as u8) as char);
+    }
+
+    // Only the heights decide which moves are possible, whatever the crane
+    let mut heights: Vec<usize> = stacks.iter().map(Vec::len).collect();
+    let mut moves: Vec<Move> = Vec::with_capacity(NUMBER_OF_MOVES);
+    while moves.len() < NUMBER_OF_MOVES {
+        let (from, to) = (
+            random.below(NUMBER_OF_STACKS),
+            random.below(NUMBER_OF_STACKS),
+        );
+        if heights[from] == 0 || from == to {
+            continue;
+        }
+        let count = 1 + random.below(heights[from].min(LARGEST_MOVE));
+        heights[from] -= count;
+        heights[to] += count;
+        moves.push(Move {
+            count,
+            from: from + 1,
+            to: to + 1,
+        });
+    }
+
+    (stacks, moves)
+}
+
+fn time<T>(description: &str, run: impl Fn() -> T) -> T {
+    let start = Instant::now();
+    let result = run();
+    println!("{}: {:?}", description, start.elapsed());
+    result
+}
+
+// How the stacks used to be kept, top first, so every move shifts the whole of both stacks
+fn move_in_lifts_top_first(stacks: &mut [Vec<char>], crate_move: &Move, lift_size: usize) {
+    if crate_move.from == crate_move.to {
+        return;
+    }
+    let (from, to) = (crate_move.from - 1, crate_move.to - 1);
+
+    let crates_to_move: Vec<char> = stacks[from].drain(0..crate_move.count).collect();
+    let landed: Vec<char> = crates_to_move
+        .chunks(lift_size.max(1))
+        .rev()
+        .flatten()
+        .copied()
+        .collect();
+    stacks[to].splice(0..0, landed);
+}
+
+fn run_top_first(
+    stacks: &[Vec<char>],
+    moves: &[Move],
+    lift_size: fn(&Move) -> usize,
+) -> Vec<Vec<char>> {
+    let mut stacks: Vec<Vec<char>> = stacks
+        .iter()
+        .map(|stack| stack.iter().rev().copied().collect())
+        .collect();
+    for crate_move in moves {
+        move_in_lifts_top_first(&mut stacks, crate_move, lift_size(crate_move));
+    }
+    // Back to bottom first, to compare
+    for stack in &mut stacks {
+        stack.reverse();
+    }
+    stacks
+}
+
+fn run(stacks: &[Vec<char>], moves: &[Move], crane: &dyn Crane) -> Vec<Vec<char>> {
+    let mut stacks = stacks.to_vec();
+    for crate_move in moves {
+        crane.apply(&mut stacks, crate_move);
+    }
+    stacks
+}
+
+pub fn run_benchmarks() {
+    let mut random = Random(0x2022_1205);
+    let (stacks, moves) = generate_stacks_and_moves(&mut random);
+    println!(
+        "{} crates on {} stacks, {} moves",
+        NUMBER_OF_CRATES, NUMBER_OF_STACKS, NUMBER_OF_MOVES
+    );
+
+    let top_first = time("CrateMover 9000, top first", || {
+        run_top_first(&stacks, &moves, |_| 1)
+    });
+    let bottom_first = time("CrateMover 9000, bottom first", || {
+        run(&stacks, &moves, &CrateMover9000)
+    });
+    assert_eq!(top_first, bottom_first);
+
+    let top_first = time("CrateMover 9001, top first", || {
+        run_top_first(&stacks, &moves, |crate_move| crate_move.count)
+    });
+    let bottom_first = time("CrateMover 9001, bottom first", || {
+        run(&stacks, &moves, &CrateMover9001)
+    });
+    assert_eq!(top_first, bottom_first);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crane::LimitedCrane;
+
+    #[test]
+    fn it_moves_crates_the_same_way_in_both_layouts() {
+        let mut random = Random(0x2022_1205);
+        let (stacks, moves) = generate_stacks_and_moves(&mut random);
+        let moves = &moves[..2_000];
+
+        assert_eq!(
+            run_top_first(&stacks, moves, |_| 1),
+            run(&stacks, moves, &CrateMover9000)
+        );
+        assert_eq!(
+            run_top_first(&stacks, moves, |crate_move| crate_move.count),
+            run(&stacks, moves, &CrateMover9001)
+        );
+        assert_eq!(
+            run_top_first(&stacks, moves, |_| 3),
+            run(&stacks, moves, &LimitedCrane { capacity: 3 })
+        );
+    }
+}
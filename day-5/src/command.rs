@@ -0,0 +1,442 @@
+use std::fmt;
+
+// Stacks are numbered from 1 in the order they're drawn, whatever their labels are
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Move {
+    pub count: usize,
+    pub from: usize,
+    pub to: usize,
+}
+
+impl Move {
+    // Written the way a procedure would, naming the stacks by their labels in the drawing
+    pub fn with_labels(&self, labels: &[String]) -> String {
+        format!(
+            "move {} from {} to {}",
+            self.count,
+            labels[self.from - 1],
+            labels[self.to - 1]
+        )
+    }
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "move {} from {} to {}", self.count, self.from, self.to)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum TokenKind<'a> {
+    Word(&'a str),
+    Number(&'a str),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Token<'a> {
+    kind: TokenKind<'a>,
+    // Counted in characters from 1, like an editor would
+    column: usize,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseMoveError {
+    InvalidCharacter {
+        line_number: usize,
+        column: usize,
+        character: char,
+    },
+    UnexpectedToken {
+        line_number: usize,
+        column: usize,
+        expected: &'static str,
+        found: String,
+    },
+    MissingToken {
+        line_number: usize,
+        expected: &'static str,
+    },
+    NumberTooLarge {
+        line_number: usize,
+        column: usize,
+        number: String,
+    },
+    NoSuchStack {
+        line_number: usize,
+        column: usize,
+        label: String,
+    },
+}
+
+impl fmt::Display for ParseMoveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseMoveError::InvalidCharacter {
+                line_number,
+                column,
+                character,
+            } => write!(
+                f,
+                "Line {}, column {}: unexpected character {:?}",
+                line_number, column, character
+            ),
+            ParseMoveError::UnexpectedToken {
+                line_number,
+                column,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Line {}, column {}: expected {} but found {:?}",
+                line_number, column, expected, found
+            ),
+            ParseMoveError::MissingToken {
+                line_number,
+                expected,
+            } => write!(
+                f,
+                "Line {}: expected {} but the line ended",
+                line_number, expected
+            ),
+            ParseMoveError::NumberTooLarge {
+                line_number,
+                column,
+                number,
+            } => write!(
+                f,
+                "Line {}, column {}: {} is too large",
+                line_number, column, number
+            ),
+            ParseMoveError::NoSuchStack {
+                line_number,
+                column,
+                label,
+            } => write!(
+                f,
+                "Line {}, column {}: there's no stack labelled {} in the drawing",
+                line_number, column, label
+            ),
+        }
+    }
+}
+
+// Splits a line into runs of letters and runs of digits, separated by any amount of whitespace
+fn tokenize(line: &str, line_number: usize) -> Result<Vec<Token<'_>>, ParseMoveError> {
+    let mut tokens: Vec<Token> = Vec::new();
+    let mut characters = line.char_indices().enumerate().peekable();
+
+    while let Some((position, (start, character))) = characters.next() {
+        if character.is_whitespace() {
+            continue;
+        }
+
+        let is_part_of_token: fn(&char) -> bool = if character.is_ascii_alphabetic() {
+            char::is_ascii_alphabetic
+        } else if character.is_ascii_digit() {
+            char::is_ascii_digit
+        } else {
+            return Err(ParseMoveError::InvalidCharacter {
+                line_number,
+                column: position + 1,
+                character,
+            });
+        };
+
+        let mut end = start + character.len_utf8();
+        while let Some((_, (next_start, next_character))) =
+            characters.next_if(|(_, (_, next_character))| is_part_of_token(next_character))
+        {
+            end = next_start + next_character.len_utf8();
+        }
+
+        let text = &line[start..end];
+        tokens.push(Token {
+            kind: if character.is_ascii_digit() {
+                TokenKind::Number(text)
+            } else {
+                TokenKind::Word(text)
+            },
+            column: position + 1,
+        });
+    }
+
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: std::vec::IntoIter<Token<'a>>,
+    line_number: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn next(&mut self, expected: &'static str) -> Result<Token<'a>, ParseMoveError> {
+        self.tokens.next().ok_or(ParseMoveError::MissingToken {
+            line_number: self.line_number,
+            expected,
+        })
+    }
+
+    fn unexpected(&self, token: &Token, expected: &'static str) -> ParseMoveError {
+        let found = match token.kind {
+            TokenKind::Word(text) | TokenKind::Number(text) => text.to_string(),
+        };
+        ParseMoveError::UnexpectedToken {
+            line_number: self.line_number,
+            column: token.column,
+            expected,
+            found,
+        }
+    }
+
+    fn keyword(&mut self, keyword: &'static str) -> Result<(), ParseMoveError> {
+        let token = self.next(keyword)?;
+        match token.kind {
+            TokenKind::Word(text) if text == keyword => Ok(()),
+            _ => Err(self.unexpected(&token, keyword)),
+        }
+    }
+
+    fn number(&mut self, expected: &'static str) -> Result<usize, ParseMoveError> {
+        let token = self.next(expected)?;
+        match token.kind {
+            TokenKind::Number(text) => text.parse().map_err(|_| ParseMoveError::NumberTooLarge {
+                line_number: self.line_number,
+                column: token.column,
+                number: text.to_string(),
+            }),
+            _ => Err(self.unexpected(&token, expected)),
+        }
+    }
+
+    // Stacks are named by their label, so this returns the position of the stack with that label
+    fn stack(&mut self, labels: &[String]) -> Result<usize, ParseMoveError> {
+        let token = self.next("a stack label")?;
+        let (TokenKind::Word(text) | TokenKind::Number(text)) = token.kind;
+        labels
+            .iter()
+            .position(|label| label == text)
+            .map(|index| index + 1)
+            .ok_or(ParseMoveError::NoSuchStack {
+                line_number: self.line_number,
+                column: token.column,
+                label: text.to_string(),
+            })
+    }
+
+    fn end(&mut self) -> Result<(), ParseMoveError> {
+        match self.tokens.next() {
+            Some(token) => Err(self.unexpected(&token, "the end of the line")),
+            None => Ok(()),
+        }
+    }
+}
+
+pub fn parse_move(
+    line: &str,
+    line_number: usize,
+    labels: &[String],
+) -> Result<Move, ParseMoveError> {
+    let mut parser = Parser {
+        tokens: tokenize(line, line_number)?.into_iter(),
+        line_number,
+    };
+
+    parser.keyword("move")?;
+    let count = parser.number("a number of crates")?;
+    parser.keyword("from")?;
+    let from = parser.stack(labels)?;
+    parser.keyword("to")?;
+    let to = parser.stack(labels)?;
+    parser.end()?;
+
+    Ok(Move { count, from, to })
+}
+
+// Blank lines are skipped, line numbers count from the first of the given lines. Each move
+// comes with its line number, so later problems with it can be reported
+pub fn parse_numbered_moves<'a>(
+    lines: impl Iterator<Item = &'a str>,
+    first_line_number: usize,
+    labels: &[String],
+) -> Result<Vec<(usize, Move)>, ParseMoveError> {
+    lines
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            let line_number = first_line_number + index;
+            parse_move(line, line_number, labels).map(|crate_move| (line_number, crate_move))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_labels(labels: &[&str]) -> Vec<String> {
+        labels.iter().map(|label| label.to_string()).collect()
+    }
+
+    #[test]
+    fn it_parses_moves_with_any_numbers_and_spacing() {
+        assert_eq!(
+            parse_move("move 1 from 2 to 1", 1, &get_labels(&["1", "2", "3"])),
+            Ok(Move {
+                count: 1,
+                from: 2,
+                to: 1
+            })
+        );
+        assert_eq!(
+            parse_move(
+                "  move   123 from 10\tto 12  ",
+                1,
+                &get_labels(&["12", "10"])
+            ),
+            Ok(Move {
+                count: 123,
+                from: 2,
+                to: 1
+            })
+        );
+        assert_eq!(
+            parse_numbered_moves(
+                ["move 0 from 1 to 2", "", "move 4 from 2 to 1"].into_iter(),
+                1,
+                &get_labels(&["1", "2"])
+            )
+            .map(|moves| moves
+                .into_iter()
+                .map(|(line_number, _)| line_number)
+                .collect()),
+            Ok(vec![1, 3])
+        );
+    }
+
+    #[test]
+    fn it_reports_malformed_lines_precisely() {
+        assert_eq!(
+            parse_move("move 2 form 1 to 3", 7, &get_labels(&["1", "2", "3"])),
+            Err(ParseMoveError::UnexpectedToken {
+                line_number: 7,
+                column: 8,
+                expected: "from",
+                found: "form".to_string()
+            })
+        );
+        assert_eq!(
+            parse_move("move two from 1 to 3", 7, &get_labels(&["1", "2", "3"])),
+            Err(ParseMoveError::UnexpectedToken {
+                line_number: 7,
+                column: 6,
+                expected: "a number of crates",
+                found: "two".to_string()
+            })
+        );
+        assert_eq!(
+            parse_move("move 2 from 1", 7, &get_labels(&["1", "2", "3"])),
+            Err(ParseMoveError::MissingToken {
+                line_number: 7,
+                expected: "to"
+            })
+        );
+        assert_eq!(
+            parse_move("move 2 from 1 to 3 now", 7, &get_labels(&["1", "2", "3"])),
+            Err(ParseMoveError::UnexpectedToken {
+                line_number: 7,
+                column: 20,
+                expected: "the end of the line",
+                found: "now".to_string()
+            })
+        );
+        assert_eq!(
+            parse_move("move -2 from 1 to 3", 7, &get_labels(&["1", "2", "3"])),
+            Err(ParseMoveError::InvalidCharacter {
+                line_number: 7,
+                column: 6,
+                character: '-'
+            })
+        );
+        assert_eq!(
+            parse_move(
+                "move 99999999999999999999 from 1 to 3",
+                7,
+                &get_labels(&["1", "2", "3"])
+            ),
+            Err(ParseMoveError::NumberTooLarge {
+                line_number: 7,
+                column: 6,
+                number: "99999999999999999999".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn it_finds_stacks_by_their_labels() {
+        let labels = get_labels(&["1", "3", "7"]);
+        assert_eq!(
+            parse_move("move 1 from 7 to 3", 1, &labels),
+            Ok(Move {
+                count: 1,
+                from: 3,
+                to: 2
+            })
+        );
+        assert_eq!(
+            parse_move("move 2 from a to bb", 1, &get_labels(&["bb", "a"])),
+            Ok(Move {
+                count: 2,
+                from: 2,
+                to: 1
+            })
+        );
+        assert_eq!(
+            Move {
+                count: 1,
+                from: 3,
+                to: 2
+            }
+            .with_labels(&labels),
+            "move 1 from 7 to 3"
+        );
+    }
+
+    #[test]
+    fn it_reports_stacks_that_do_not_exist() {
+        let labels = get_labels(&["1", "3", "7"]);
+        assert_eq!(
+            parse_move("move 1 from 2 to 1", 3, &labels),
+            Err(ParseMoveError::NoSuchStack {
+                line_number: 3,
+                column: 13,
+                label: "2".to_string()
+            })
+        );
+        assert_eq!(
+            parse_move("move 1 from 1 to 0", 3, &labels)
+                .unwrap_err()
+                .to_string(),
+            "Line 3, column 18: there's no stack labelled 0 in the drawing"
+        );
+        assert_eq!(
+            parse_move("move 1 from 1 to 07", 3, &labels),
+            Err(ParseMoveError::NoSuchStack {
+                line_number: 3,
+                column: 18,
+                label: "07".to_string()
+            })
+        );
+        assert_eq!(
+            parse_numbered_moves(
+                ["move 1 from 1 to 3", "move 1 from 3 to 5"].into_iter(),
+                10,
+                &labels
+            ),
+            Err(ParseMoveError::NoSuchStack {
+                line_number: 11,
+                column: 18,
+                label: "5".to_string()
+            })
+        );
+    }
+}
@@ -0,0 +1,138 @@
+use crate::command::Move;
+
+pub trait Crane {
+    fn name(&self) -> String;
+    // Stacks are kept bottom first. Panics if the move asks for more crates than its stack has,
+    // a dry run finds those moves without panicking
+    fn apply(&self, stacks: &mut [Vec<char>], crate_move: &Move);
+}
+
+// Lifts the crates off in blocks of up to lift_size, each block keeping its order, and puts
+// every block down on top of the one before. With the tops at the ends of the vectors, that
+// only touches the crates being moved
+fn move_in_lifts(stacks: &mut [Vec<char>], crate_move: &Move, lift_size: usize) {
+    let available = stacks[crate_move.from - 1].len();
+    assert!(
+        crate_move.count <= available,
+        "{} asks for {} crates but there are only {}",
+        crate_move,
+        crate_move.count,
+        available
+    );
+    if crate_move.from == crate_move.to {
+        return;
+    }
+    let [from, to] = stacks
+        .get_disjoint_mut([crate_move.from - 1, crate_move.to - 1])
+        .unwrap();
+
+    // The top block is lifted first, so it ends up lowest
+    let first_moved = from.len() - crate_move.count;
+    to.extend(from[first_moved..].rchunks(lift_size.max(1)).flatten());
+    from.truncate(first_moved);
+}
+
+// Moves one crate at a time, so the moved crates end up in reverse order
+pub struct CrateMover9000;
+
+impl Crane for CrateMover9000 {
+    fn name(&self) -> String {
+        "CrateMover 9000".to_string()
+    }
+
+    fn apply(&self, stacks: &mut [Vec<char>], crate_move: &Move) {
+        move_in_lifts(stacks, crate_move, 1);
+    }
+}
+
+// Moves all the crates at once, so they keep their order
+pub struct CrateMover9001;
+
+impl Crane for CrateMover9001 {
+    fn name(&self) -> String {
+        "CrateMover 9001".to_string()
+    }
+
+    fn apply(&self, stacks: &mut [Vec<char>], crate_move: &Move) {
+        move_in_lifts(stacks, crate_move, crate_move.count);
+    }
+}
+
+// Can only lift so many crates at once, so larger moves are split into several lifts
+pub struct LimitedCrane {
+    pub capacity: usize,
+}
+
+impl Crane for LimitedCrane {
+    fn name(&self) -> String {
+        format!("Crane lifting up to {}", self.capacity)
+    }
+
+    fn apply(&self, stacks: &mut [Vec<char>], crate_move: &Move) {
+        move_in_lifts(stacks, crate_move, self.capacity);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply(crane: &dyn Crane, count: usize) -> Vec<Vec<char>> {
+        let mut stacks = vec![vec!['E', 'D', 'C', 'B', 'A'], vec!['Z']];
+        crane.apply(
+            &mut stacks,
+            &Move {
+                count,
+                from: 1,
+                to: 2,
+            },
+        );
+        stacks
+    }
+
+    #[test]
+    fn it_moves_crates_in_lifts() {
+        assert_eq!(
+            apply(&CrateMover9000, 3),
+            [vec!['E', 'D'], vec!['Z', 'A', 'B', 'C']]
+        );
+        assert_eq!(
+            apply(&CrateMover9001, 3),
+            [vec!['E', 'D'], vec!['Z', 'C', 'B', 'A']]
+        );
+        assert_eq!(
+            apply(&LimitedCrane { capacity: 2 }, 5),
+            [vec![], vec!['Z', 'B', 'A', 'D', 'C', 'E']]
+        );
+        assert_eq!(
+            apply(&LimitedCrane { capacity: 1 }, 4),
+            apply(&CrateMover9000, 4)
+        );
+        assert_eq!(
+            apply(&LimitedCrane { capacity: 9 }, 4),
+            apply(&CrateMover9001, 4)
+        );
+    }
+
+    #[test]
+    fn it_leaves_moves_onto_the_same_stack_alone() {
+        let mut stacks = vec![vec!['A', 'B', 'C']];
+        for crane in [&CrateMover9000 as &dyn Crane, &CrateMover9001] {
+            crane.apply(
+                &mut stacks,
+                &Move {
+                    count: 3,
+                    from: 1,
+                    to: 1,
+                },
+            );
+            assert_eq!(stacks, [vec!['A', 'B', 'C']]);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "move 6 from 1 to 2 asks for 6 crates but there are only 5")]
+    fn it_rejects_moves_with_too_few_crates() {
+        apply(&CrateMover9001, 6);
+    }
+}
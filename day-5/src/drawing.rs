@@ -0,0 +1,401 @@
+use std::fmt;
+
+// Each stack is kept bottom first, so its top crate is the last one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Drawing {
+    pub labels: Vec<String>,
+    pub stacks: Vec<Vec<char>>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseDrawingError {
+    NoLabels,
+    InvalidCrate {
+        line_number: usize,
+        column: usize,
+    },
+    SharedStack {
+        line_number: usize,
+        column: usize,
+        label: String,
+    },
+    FloatingCrate {
+        line_number: usize,
+        column: usize,
+    },
+}
+
+impl fmt::Display for ParseDrawingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseDrawingError::NoLabels => write!(f, "The drawing has no row of stack labels"),
+            ParseDrawingError::InvalidCrate {
+                line_number,
+                column,
+            } => write!(
+                f,
+                "Line {}, column {}: expected a crate like [A]",
+                line_number, column
+            ),
+            ParseDrawingError::SharedStack {
+                line_number,
+                column,
+                label,
+            } => write!(
+                f,
+                "Line {}, column {}: another crate in this row is already on stack {}",
+                line_number, column, label
+            ),
+            ParseDrawingError::FloatingCrate {
+                line_number,
+                column,
+            } => write!(
+                f,
+                "Line {}, column {}: crate has nothing underneath it",
+                line_number, column
+            ),
+        }
+    }
+}
+
+// Draws the stacks the way the puzzle does, each column as wide as its label or a crate, with the
+// crate's letter over the label and no trailing whitespace, so the result parses back the same
+impl fmt::Display for Drawing {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let widths: Vec<usize> = self
+            .labels
+            .iter()
+            .map(|label| label.chars().count().max(3))
+            .collect();
+        let height = self.stacks.iter().map(Vec::len).max().unwrap_or(0);
+
+        let mut lines: Vec<String> = Vec::new();
+        for row in (0..height).rev() {
+            let mut line = String::new();
+            for (stack, width) in self.stacks.iter().zip(&widths) {
+                let padding = (width - 3) / 2;
+                match stack.get(row) {
+                    Some(letter) => line.push_str(&format!(
+                        "{}[{}]{}",
+                        " ".repeat(padding),
+                        letter,
+                        " ".repeat(width - 3 - padding + 1)
+                    )),
+                    None => line.push_str(&" ".repeat(width + 1)),
+                }
+            }
+            lines.push(line.trim_end().to_string());
+        }
+
+        let mut label_line = String::new();
+        for (label, width) in self.labels.iter().zip(&widths) {
+            let length = label.chars().count();
+            // Centred on the letter, which is what decides the stack when parsing
+            let start = (width - 3) / 2 + 1 - (length - 1) / 2;
+            label_line.push_str(&format!(
+                "{}{}{}",
+                " ".repeat(start),
+                label,
+                " ".repeat(width - start - length + 1)
+            ));
+        }
+        lines.push(label_line.trim_end().to_string());
+
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+// The character column each label covers, as [start, end)
+struct LabelSpan {
+    start: usize,
+    end: usize,
+}
+
+fn get_label_spans(line: &str) -> (Vec<String>, Vec<LabelSpan>) {
+    let mut labels: Vec<String> = Vec::new();
+    let mut spans: Vec<LabelSpan> = Vec::new();
+    let characters: Vec<char> = line.chars().collect();
+    let mut column = 0;
+
+    while column < characters.len() {
+        if characters[column].is_whitespace() {
+            column += 1;
+            continue;
+        }
+        let start = column;
+        while column < characters.len() && !characters[column].is_whitespace() {
+            column += 1;
+        }
+        labels.push(characters[start..column].iter().collect());
+        spans.push(LabelSpan { start, end: column });
+    }
+
+    (labels, spans)
+}
+
+// A crate belongs to the label it sits over, or failing that the nearest one
+fn get_stack_for_column(spans: &[LabelSpan], column: usize) -> usize {
+    let distance = |span: &LabelSpan| {
+        if column < span.start {
+            span.start - column
+        } else if column >= span.end {
+            column + 1 - span.end
+        } else {
+            0
+        }
+    };
+
+    // The spans are in order, so the nearest is next to the first one that ends after the column
+    let after = spans.partition_point(|span| span.end <= column);
+    [after.checked_sub(1), Some(after)]
+        .into_iter()
+        .flatten()
+        .filter(|stack| *stack < spans.len())
+        .min_by_key(|stack| distance(&spans[*stack]))
+        .unwrap()
+}
+
+// Returns each crate in the row with the column of its letter, counted from 1
+fn get_crates(line: &str, line_number: usize) -> Result<Vec<(usize, char)>, ParseDrawingError> {
+    let characters: Vec<char> = line.chars().collect();
+    let mut crates: Vec<(usize, char)> = Vec::new();
+    let mut column = 0;
+
+    while column < characters.len() {
+        if characters[column].is_whitespace() {
+            column += 1;
+            continue;
+        }
+        match characters[column..] {
+            ['[', letter, ']', ..] if !letter.is_whitespace() => {
+                crates.push((column + 2, letter));
+                column += 3;
+            }
+            _ => {
+                return Err(ParseDrawingError::InvalidCrate {
+                    line_number,
+                    column: column + 1,
+                })
+            }
+        }
+    }
+
+    Ok(crates)
+}
+
+// The drawing ends with its row of labels, and the line numbers count from its first line. The
+// labels decide where each stack's column is, so they can be any width and any distance apart
+pub fn parse_drawing(lines: &[&str]) -> Result<Drawing, ParseDrawingError> {
+    let (label_line, crate_lines) = lines.split_last().ok_or(ParseDrawingError::NoLabels)?;
+    let (labels, spans) = get_label_spans(label_line);
+    if labels.is_empty() {
+        return Err(ParseDrawingError::NoLabels);
+    }
+
+    let mut stacks: Vec<Vec<char>> = vec![Vec::new(); labels.len()];
+    // For each stack, where the crate in the row above was, so a gap beneath it can be reported
+    let mut last_crate_above: Vec<Option<(usize, usize)>> = vec![None; labels.len()];
+
+    for (index, line) in crate_lines.iter().enumerate() {
+        let line_number = index + 1;
+        let mut is_filled = vec![false; labels.len()];
+
+        for (column, letter) in get_crates(line, line_number)? {
+            let stack = get_stack_for_column(&spans, column - 1);
+            if is_filled[stack] {
+                return Err(ParseDrawingError::SharedStack {
+                    line_number,
+                    column,
+                    label: labels[stack].clone(),
+                });
+            }
+            is_filled[stack] = true;
+            stacks[stack].push(letter);
+            last_crate_above[stack] = Some((line_number, column));
+        }
+
+        for stack in 0..labels.len() {
+            if !is_filled[stack] && !stacks[stack].is_empty() {
+                let (line_number, column) = last_crate_above[stack].unwrap();
+                return Err(ParseDrawingError::FloatingCrate {
+                    line_number,
+                    column,
+                });
+            }
+        }
+    }
+
+    // The rows were read from the top down
+    for stack in &mut stacks {
+        stack.reverse();
+    }
+    Ok(Drawing { labels, stacks })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::Random;
+    use std::fs;
+
+    // Every stack gets a column wide enough for its label and a crate, with the label placed
+    // anywhere under the crate's letter and random trailing whitespace on every line
+    fn generate_drawing(random: &mut Random, number_of_stacks: usize) -> (String, Drawing) {
+        let labels: Vec<String> = (1..=number_of_stacks)
+            .map(|label| label.to_string())
+            .collect();
+        let height = random.below(8);
+        let stacks: Vec<Vec<char>> = (0..number_of_stacks)
+            .map(|_| {
+                (0..random.below(height + 1))
+                    .map(|_| (b'A' + random.below(26) as u8) as char)
+                    .collect()
+            })
+            .collect();
+
+        let mut label_line = String::new();
+        let mut letter_columns: Vec<usize> = Vec::new();
+        for label in &labels {
+            let letter_column = label_line.len() + label.len() + random.below(3);
+            let label_start = letter_column + 1 - label.len() + random.below(label.len());
+            label_line.push_str(&" ".repeat(label_start - label_line.len()));
+            label_line.push_str(label);
+            letter_columns.push(letter_column);
+            label_line.push_str(&" ".repeat(1 + random.below(3)));
+        }
+
+        let mut lines: Vec<String> = Vec::new();
+        for row in (0..height).rev() {
+            let mut line: Vec<char> = Vec::new();
+            for (stack, letter_column) in stacks.iter().zip(&letter_columns) {
+                if row < stack.len() {
+                    line.resize(letter_column + 2, ' ');
+                    line[letter_column - 1] = '[';
+                    line[*letter_column] = stack[row];
+                    line[letter_column + 1] = ']';
+                }
+            }
+            lines.push(line.into_iter().collect());
+        }
+        lines.push(label_line.trim_end().to_string());
+
+        let text = lines
+            .iter()
+            .map(|line| format!("{}{}", line, " ".repeat(random.below(4))))
+            .collect::<Vec<String>>()
+            .join("\n");
+        (text, Drawing { labels, stacks })
+    }
+
+    #[test]
+    fn it_parses_the_test_drawing() {
+        let file_contents =
+            fs::read_to_string("./test.txt").expect("Should have been able to read the file");
+        let lines: Vec<&str> = file_contents.lines().take(4).collect();
+
+        assert_eq!(
+            parse_drawing(&lines),
+            Ok(Drawing {
+                labels: vec!["1".to_string(), "2".to_string(), "3".to_string()],
+                stacks: vec![vec!['Z', 'N'], vec!['M', 'C', 'D'], vec!['P']],
+            })
+        );
+    }
+
+    #[test]
+    fn it_parses_wide_labels_and_ragged_lines() {
+        let lines = [
+            "                 [X]",
+            "[A]        [B]   [Y]",
+            " 9   10    100  1000   ",
+        ];
+
+        assert_eq!(
+            parse_drawing(&lines),
+            Ok(Drawing {
+                labels: ["9", "10", "100", "1000"].map(String::from).to_vec(),
+                stacks: vec![vec!['A'], vec![], vec!['B'], vec!['Y', 'X']],
+            })
+        );
+    }
+
+    #[test]
+    fn it_parses_generated_drawings_with_hundreds_of_stacks() {
+        let mut random = Random(0x2022_1205);
+        for number_of_stacks in [1, 9, 10, 11, 99, 100, 101, 250, 999, 1000] {
+            let (text, drawing) = generate_drawing(&mut random, number_of_stacks);
+            let lines: Vec<&str> = text.lines().collect();
+
+            assert_eq!(parse_drawing(&lines), Ok(drawing));
+        }
+    }
+
+    #[test]
+    fn it_renders_the_test_drawing_as_it_was_drawn() {
+        let file_contents =
+            fs::read_to_string("./test.txt").expect("Should have been able to read the file");
+        let lines: Vec<&str> = file_contents.lines().take(4).collect();
+
+        assert_eq!(parse_drawing(&lines).unwrap().to_string(), lines.join("\n"));
+    }
+
+    #[test]
+    fn it_parses_rendered_drawings_back_to_the_same_stacks() {
+        let mut random = Random(0x2022_1205);
+        for number_of_stacks in [1, 9, 10, 11, 99, 100, 101, 250, 999, 1000] {
+            let (_, drawing) = generate_drawing(&mut random, number_of_stacks);
+            let text = drawing.to_string();
+            let lines: Vec<&str> = text.lines().collect();
+
+            assert_eq!(parse_drawing(&lines), Ok(drawing));
+        }
+
+        let drawing = Drawing {
+            labels: ["a", "bb", "ccc", "dddd", "eeeee", "ü"]
+                .map(String::from)
+                .to_vec(),
+            stacks: vec![
+                vec![],
+                vec!['X'],
+                vec!['Y', 'Z'],
+                vec![],
+                vec!['W'],
+                vec!['V'],
+            ],
+        };
+        let text = drawing.to_string();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(parse_drawing(&lines), Ok(drawing));
+    }
+
+    #[test]
+    fn it_reports_malformed_drawings() {
+        assert_eq!(parse_drawing(&[]), Err(ParseDrawingError::NoLabels));
+        assert_eq!(
+            parse_drawing(&["[A]", "   "]),
+            Err(ParseDrawingError::NoLabels)
+        );
+        assert_eq!(
+            parse_drawing(&["[A] [B", " 1   2"]),
+            Err(ParseDrawingError::InvalidCrate {
+                line_number: 1,
+                column: 5
+            })
+        );
+        assert_eq!(
+            parse_drawing(&["[A][B]", " 1"]),
+            Err(ParseDrawingError::SharedStack {
+                line_number: 1,
+                column: 5,
+                label: "1".to_string()
+            })
+        );
+        assert_eq!(
+            parse_drawing(&["    [A]", "        ", "[B] [C]", " 1   2"]),
+            Err(ParseDrawingError::FloatingCrate {
+                line_number: 1,
+                column: 6
+            })
+        );
+    }
+}
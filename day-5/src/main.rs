@@ -1,149 +1,263 @@
-use std::fs;
-use substring::Substring;
+mod bench;
+mod command;
+mod crane;
+mod drawing;
+mod planner;
+mod random;
+mod replay;
+mod validation;
 
-fn get_numbers_from_command(command: &str, index: usize) -> usize {
-    let preceding_char = command.chars().nth(index - 1);
-    let number = command.chars().nth(index);
-    let succeeding_char = command.chars().nth(index + 1);
+use command::{parse_numbered_moves, Move};
+use crane::{Crane, CrateMover9000, CrateMover9001, LimitedCrane};
+use drawing::{parse_drawing, Drawing};
+use planner::{plan, Target};
+use replay::Replay;
+use std::io::{self, BufRead};
+use std::{env, fs};
+use validation::{dry_run, OnIllegalMove};
 
-    if preceding_char.is_none() && number.is_none() && succeeding_char.is_none() {
-        panic!("No number found");
-    }
-
-    if !preceding_char.is_none() && !number.is_none() && !succeeding_char.is_none() {
-        let first_number_as_string = preceding_char.unwrap().to_string();
-        let second_number_as_string = number.unwrap().to_string();
-        let third_number_as_string = succeeding_char.unwrap().to_string();
-        let full_string = format!(
-            "{}{}{}",
-            first_number_as_string, second_number_as_string, third_number_as_string
-        );
+// Shown in the message in place of the top crate of an empty stack
+const EMPTY_STACK: char = '-';
 
-        return full_string.replace(" ", "").parse::<usize>().unwrap();
-    }
+const MAX_PLANNING_STATES: usize = 10_000_000;
 
-    return number.unwrap().to_digit(10).unwrap() as usize;
+fn get_top_layer_of_stacks(stacks: &[Vec<char>]) -> String {
+    stacks
+        .iter()
+        .map(|stack| stack.last().copied().unwrap_or(EMPTY_STACK))
+        .collect()
 }
 
-fn apply_command(stacks: &mut Vec<Vec<char>>, command: &str) {
-    let quantity_to_move = get_numbers_from_command(command, 5);
-    let mut index_for_original_stack_number = 12;
-    let mut index_for_new_stack_number = 17;
-    if quantity_to_move > 9 {
-        index_for_original_stack_number = 13;
-        index_for_new_stack_number = 18;
-    }
-    let original_stack_number_index =
-        get_numbers_from_command(command, index_for_original_stack_number) - 1;
-    let new_stack_number_index = get_numbers_from_command(command, index_for_new_stack_number) - 1;
+fn get_drawing_and_numbered_moves(file_path: &str) -> (Drawing, Vec<(usize, Move)>) {
+    let file_contents =
+        fs::read_to_string(file_path).expect("Should have been able to read the file");
 
-    let stacks_to_move: Vec<char> = stacks[original_stack_number_index][0..quantity_to_move]
+    // The drawing runs up to the first blank line and the moves follow it
+    let lines: Vec<&str> = file_contents.lines().collect();
+    let blank_line = lines
         .iter()
-        // .rev()
-        .cloned()
-        .collect();
-    stacks[new_stack_number_index] = [
-        stacks_to_move.as_slice(),
-        stacks[new_stack_number_index].as_slice(),
-    ]
-    .concat();
-    stacks[original_stack_number_index].drain(0..quantity_to_move);
+        .position(|line| line.trim().is_empty())
+        .unwrap_or(lines.len());
+
+    let drawing = parse_drawing(&lines[..blank_line]).unwrap_or_else(|error| panic!("{}", error));
+    let moves = parse_numbered_moves(
+        lines.iter().skip(blank_line + 1).copied(),
+        blank_line + 2,
+        &drawing.labels,
+    )
+    .unwrap_or_else(|error| panic!("{}", error));
+
+    (drawing, moves)
 }
 
-fn remove_whitespace(string: &str) -> String {
-    string
-        .chars()
-        .filter(|char| !char.is_whitespace())
-        .collect()
+fn get_drawing_and_moves(file_path: &str) -> (Drawing, Vec<Move>) {
+    let (drawing, moves) = get_drawing_and_numbered_moves(file_path);
+    (
+        drawing,
+        moves
+            .into_iter()
+            .map(|(_, crate_move)| crate_move)
+            .collect(),
+    )
 }
 
-fn get_top_layer_of_stacks(stacks: &mut Vec<Vec<char>>) -> String {
-    return stacks
-        .iter()
-        .map(|stack| stack.first().unwrap())
-        .into_iter()
-        .collect();
+fn get_stacks_of_crates(file_path: &str, crane: &dyn Crane) -> String {
+    let (mut drawing, moves) = get_drawing_and_moves(file_path);
+
+    for crate_move in &moves {
+        crane.apply(&mut drawing.stacks, crate_move);
+    }
+
+    get_top_layer_of_stacks(&drawing.stacks)
 }
 
-fn get_stacks_of_crates(file_path: &str) -> String {
-    let file_contents =
-        fs::read_to_string(file_path).expect("Should have been able to read the file");
+fn print_messages(file_path: &str, cranes: &[&dyn Crane]) {
+    for crane in cranes {
+        println!(
+            "{}: {}",
+            crane.name(),
+            get_stacks_of_crates(file_path, *crane)
+        );
+    }
+}
 
-    let mut number_of_stacks: u32 = 0;
-    let mut stack_numbering_line: u32 = 0;
-
-    for (index, line) in file_contents.lines().enumerate() {
-        let line_without_whitespace = remove_whitespace(line);
-
-        let is_row_of_numbers = line_without_whitespace
-            .chars()
-            .all(|char| char::is_digit(char, 10))
-            && line_without_whitespace.len() > 0;
-
-        if is_row_of_numbers {
-            number_of_stacks = line_without_whitespace
-                .chars()
-                .last()
-                .unwrap()
-                .to_digit(10)
-                .unwrap();
-            stack_numbering_line = (index + 1) as u32;
-        }
+// 9000 and 9001 are the CrateMovers, anything else is the lift capacity of a limited crane
+fn get_crane(text: &str) -> Box<dyn Crane> {
+    match text {
+        "9000" => Box::new(CrateMover9000),
+        "9001" => Box::new(CrateMover9001),
+        capacity => Box::new(LimitedCrane {
+            capacity: capacity
+                .parse()
+                .expect("Crane should be 9000, 9001 or a lift capacity"),
+        }),
     }
+}
 
-    let stacks: &mut Vec<Vec<char>> = &mut Vec::new();
+fn print_dry_run(file_path: &str, crane: &dyn Crane, on_illegal_move: OnIllegalMove) {
+    let (drawing, moves) = get_drawing_and_numbered_moves(file_path);
+    let dry_run = dry_run(&drawing, &moves, crane, on_illegal_move);
 
-    for _ in 0..number_of_stacks {
-        stacks.push(vec![]);
+    for problem in &dry_run.problems {
+        println!("{}", problem);
     }
+    println!(
+        "{} problems, {}: {}",
+        dry_run.problems.len(),
+        crane.name(),
+        get_top_layer_of_stacks(&dry_run.stacks)
+    );
+}
 
-    for (index, stack_line) in file_contents.lines().enumerate() {
-        let line_number = index + 1;
-        if line_number == stack_numbering_line as usize {
-            break;
-        }
+// Prints the start drawing followed by the planned moves, so the output is itself a puzzle
+// file that the other modes can run
+fn print_plan(file_path: &str, crane: &dyn Crane, target: &Target) {
+    let (drawing, _) = get_drawing_and_moves(file_path);
+    let moves = plan(&drawing.stacks, target, crane, MAX_PLANNING_STATES)
+        .unwrap_or_else(|error| panic!("{}", error));
 
-        let mut string_stack_index: (usize, usize) = (0, 0);
+    println!("{}\n", drawing);
+    for crate_move in moves {
+        println!("{}", crate_move.with_labels(&drawing.labels));
+    }
+}
 
-        while string_stack_index.0 < number_of_stacks as usize {
-            let crate_to_add =
-                stack_line.substring(string_stack_index.1 + 1, string_stack_index.1 + 2);
+fn run_replay(replay: &mut Replay) {
+    println!("{}", replay.frame());
+    println!("Enter to step forward, p to step back, a move number to jump to it, e <file> to export every frame, q to quit");
 
-            if remove_whitespace(crate_to_add).len() > 0 {
-                stacks[string_stack_index.0].push(crate_to_add.chars().nth(0).unwrap());
+    for line in io::stdin().lock().lines() {
+        let line = line.expect("Should have been able to read from stdin");
+        match line.split_whitespace().collect::<Vec<&str>>()[..] {
+            [] | ["n"] => {
+                if !replay.forward() {
+                    println!("Move {} was the last one", replay.step());
+                    continue;
+                }
+            }
+            ["p"] => {
+                if !replay.back() {
+                    println!("Already at the start");
+                    continue;
+                }
+            }
+            ["e", file_path] => {
+                match replay.export(file_path) {
+                    Ok(()) => println!(
+                        "Wrote {} frames to {}",
+                        replay.number_of_moves() + 1,
+                        file_path
+                    ),
+                    Err(error) => println!("Couldn't write {}: {}", file_path, error),
+                }
+                continue;
+            }
+            ["q"] => break,
+            [step] if step.parse::<usize>().is_ok() => replay.jump_to(step.parse().unwrap()),
+            _ => {
+                println!("Unknown command {:?}", line);
+                continue;
             }
-            string_stack_index.0 += 1;
-            string_stack_index.1 += 4;
         }
+        println!("{}", replay.frame());
     }
-
-    for line in file_contents
-        .lines()
-        .skip((stack_numbering_line + 1) as usize)
-    {
-        apply_command(stacks, line);
-    }
-
-    return get_top_layer_of_stacks(stacks);
 }
 
 fn main() {
-    get_stacks_of_crates("./input.txt");
+    let arguments: Vec<String> = env::args().skip(1).collect();
+
+    match arguments.iter().map(String::as_str).collect::<Vec<&str>>()[..] {
+        // cargo run --release -- bench
+        ["bench"] => bench::run_benchmarks(),
+        // cargo run -- cranes <file> [lift capacity]...
+        ["cranes", file_path, ref capacities @ ..] => {
+            let limited_cranes: Vec<LimitedCrane> = capacities
+                .iter()
+                .map(|capacity| LimitedCrane {
+                    capacity: capacity.parse().expect("Lift capacity should be a number"),
+                })
+                .collect();
+
+            let mut cranes: Vec<&dyn Crane> = vec![&CrateMover9000, &CrateMover9001];
+            cranes.extend(limited_cranes.iter().map(|crane| crane as &dyn Crane));
+            print_messages(file_path, &cranes);
+        }
+        // cargo run -- check <file> <crane> [skip|clamp]
+        ["check", file_path, crane, ref mode @ ..] => {
+            let on_illegal_move = match mode {
+                [] | ["skip"] => OnIllegalMove::Skip,
+                ["clamp"] => OnIllegalMove::Clamp,
+                _ => panic!("Illegal moves should either be skipped or clamped"),
+            };
+            print_dry_run(file_path, get_crane(crane).as_ref(), on_illegal_move);
+        }
+        // cargo run -- plan <file> <crane> stacks <target file>
+        ["plan", file_path, crane, "stacks", target_file_path] => {
+            let (target, _) = get_drawing_and_moves(target_file_path);
+            print_plan(
+                file_path,
+                get_crane(crane).as_ref(),
+                &Target::Stacks(target.stacks),
+            );
+        }
+        // cargo run -- plan <file> <crane> top <message>
+        ["plan", file_path, crane, "top", message] => {
+            print_plan(
+                file_path,
+                get_crane(crane).as_ref(),
+                &Target::TopLayer(message.to_string()),
+            );
+        }
+        // cargo run -- replay <file> <crane>
+        ["replay", file_path, crane] => {
+            let (drawing, moves) = get_drawing_and_moves(file_path);
+            let crane = get_crane(crane);
+            run_replay(&mut Replay::new(drawing, moves, crane.as_ref()));
+        }
+        // cargo run -- export <file> <crane> <output file>
+        ["export", file_path, crane, output_file_path] => {
+            let (drawing, moves) = get_drawing_and_moves(file_path);
+            let crane = get_crane(crane);
+            Replay::new(drawing, moves, crane.as_ref())
+                .export(output_file_path)
+                .expect("Should have been able to write the frames");
+        }
+        _ => {
+            let top_layer_of_stacks = get_stacks_of_crates("./input.txt", &CrateMover9000);
+            println!("Part 1: {}", top_layer_of_stacks);
+            let top_layer_of_stacks = get_stacks_of_crates("./input.txt", &CrateMover9001);
+            println!("Part 2: {}", top_layer_of_stacks);
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::crane::{CrateMover9000, CrateMover9001, LimitedCrane};
     use crate::get_stacks_of_crates;
 
     #[test]
     fn it_returns_expected_message_for_test_file() {
-        let number_of_overlaps = get_stacks_of_crates("./test.txt");
+        let number_of_overlaps = get_stacks_of_crates("./test.txt", &CrateMover9000);
         assert_eq!(number_of_overlaps, "CMZ");
     }
 
     #[test]
     fn it_returns_expected_message_for_input_file() {
-        let number_of_overlaps = get_stacks_of_crates("./input.txt");
+        let number_of_overlaps = get_stacks_of_crates("./input.txt", &CrateMover9000);
         assert_eq!(number_of_overlaps, "RFFFWBPNS");
     }
+
+    #[test]
+    fn it_returns_expected_message_for_each_crane() {
+        assert_eq!(get_stacks_of_crates("./test.txt", &CrateMover9001), "MCD");
+        assert_eq!(
+            get_stacks_of_crates("./input.txt", &CrateMover9001),
+            "CQQBBJFCS"
+        );
+        assert_eq!(
+            get_stacks_of_crates("./test.txt", &LimitedCrane { capacity: 2 }),
+            "MCZ"
+        );
+    }
 }
@@ -0,0 +1,338 @@
+use crate::command::Move;
+use crate::crane::Crane;
+use crate::get_top_layer_of_stacks;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::fmt;
+
+pub enum Target {
+    // Every stack exactly as given, bottom first
+    Stacks(Vec<Vec<char>>),
+    // Only the crates on top, as the message reads them
+    TopLayer(String),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum PlanError {
+    StackCountMismatch { start: usize, target: usize },
+    DifferentCrates,
+    Unreachable,
+    TooManyStates(usize),
+}
+
+impl fmt::Display for PlanError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PlanError::StackCountMismatch { start, target } => write!(
+                f,
+                "The start has {} stacks but the target has {}",
+                start, target
+            ),
+            PlanError::DifferentCrates => {
+                write!(f, "The target doesn't have the same crates as the start")
+            }
+            PlanError::Unreachable => write!(f, "No sequence of moves reaches the target"),
+            PlanError::TooManyStates(states) => {
+                write!(f, "Gave up after looking at {} arrangements", states)
+            }
+        }
+    }
+}
+
+// The number of crates at the bottom of the stack that are already where the target wants them
+fn get_settled_crates(stack: &[char], target: &[char]) -> usize {
+    stack
+        .iter()
+        .zip(target)
+        .take_while(|(current, wanted)| current == wanted)
+        .count()
+}
+
+// A lower bound on the moves still needed. Every stack with an unsettled crate has to be moved
+// from at least once and every stack that's short of its target moved onto at least once, and
+// one move is one of each. For the top layer, a wrong top means that stack is touched at least
+// once, and one move touches two stacks. Either way one move changes the bound by at most one,
+// so the first time A* takes an arrangement off the queue it has the shortest path there
+fn get_lower_bound(stacks: &[Vec<char>], target: &Target) -> usize {
+    match target {
+        Target::Stacks(target_stacks) => {
+            let (mut to_clear, mut to_fill) = (0, 0);
+            for (stack, target_stack) in stacks.iter().zip(target_stacks) {
+                let settled = get_settled_crates(stack, target_stack);
+                to_clear += usize::from(stack.len() > settled);
+                to_fill += usize::from(target_stack.len() > settled);
+            }
+            to_clear.max(to_fill)
+        }
+        Target::TopLayer(message) => {
+            let wrong_tops = get_top_layer_of_stacks(stacks)
+                .chars()
+                .zip(message.chars())
+                .filter(|(top, wanted)| top != wanted)
+                .count();
+            wrong_tops.div_ceil(2)
+        }
+    }
+}
+
+fn is_reached(stacks: &[Vec<char>], target: &Target) -> bool {
+    match target {
+        Target::Stacks(target_stacks) => stacks == target_stacks,
+        Target::TopLayer(message) => get_top_layer_of_stacks(stacks) == *message,
+    }
+}
+
+fn check_target(start: &[Vec<char>], target: &Target) -> Result<(), PlanError> {
+    let target_stacks = match target {
+        Target::Stacks(stacks) => stacks.len(),
+        Target::TopLayer(message) => message.chars().count(),
+    };
+    if target_stacks != start.len() {
+        return Err(PlanError::StackCountMismatch {
+            start: start.len(),
+            target: target_stacks,
+        });
+    }
+
+    if let Target::Stacks(target_stacks) = target {
+        let sorted_crates = |stacks: &[Vec<char>]| {
+            let mut crates: Vec<char> = stacks.iter().flatten().copied().collect();
+            crates.sort_unstable();
+            crates
+        };
+        if sorted_crates(start) != sorted_crates(target_stacks) {
+            return Err(PlanError::DifferentCrates);
+        }
+    }
+    Ok(())
+}
+
+struct Node {
+    stacks: Vec<Vec<char>>,
+    moves_so_far: usize,
+    // The node this one was reached from, and the move that did it
+    parent: Option<(usize, Move)>,
+}
+
+// A* over arrangements of the crates, where every move of any number of crates between any two
+// stacks costs one. Gives up once more than max_states different arrangements have been seen
+pub fn plan(
+    start: &[Vec<char>],
+    target: &Target,
+    crane: &dyn Crane,
+    max_states: usize,
+) -> Result<Vec<Move>, PlanError> {
+    check_target(start, target)?;
+
+    let mut nodes: Vec<Node> = vec![Node {
+        stacks: start.to_vec(),
+        moves_so_far: 0,
+        parent: None,
+    }];
+    let mut fewest_moves: HashMap<Vec<Vec<char>>, usize> = HashMap::from([(start.to_vec(), 0)]);
+    let mut expanded: HashSet<Vec<Vec<char>>> = HashSet::new();
+    // Ties go to the arrangement closer to the target, then to the one found first
+    let mut queue = BinaryHeap::from([Reverse((get_lower_bound(start, target), 0, 0))]);
+
+    while let Some(Reverse((_, _, index))) = queue.pop() {
+        if expanded.contains(&nodes[index].stacks) {
+            continue;
+        }
+        if is_reached(&nodes[index].stacks, target) {
+            let mut moves: Vec<Move> = Vec::new();
+            let mut current = index;
+            while let Some((parent, crate_move)) = nodes[current].parent {
+                moves.push(crate_move);
+                current = parent;
+            }
+            moves.reverse();
+            return Ok(moves);
+        }
+        expanded.insert(nodes[index].stacks.clone());
+
+        let moves_so_far = nodes[index].moves_so_far + 1;
+        for from in 1..=start.len() {
+            for to in (1..=start.len()).filter(|to| *to != from) {
+                for count in 1..=nodes[index].stacks[from - 1].len() {
+                    let crate_move = Move { count, from, to };
+                    let mut stacks = nodes[index].stacks.clone();
+                    crane.apply(&mut stacks, &crate_move);
+
+                    if fewest_moves
+                        .get(&stacks)
+                        .is_some_and(|fewest| *fewest <= moves_so_far)
+                    {
+                        continue;
+                    }
+                    fewest_moves.insert(stacks.clone(), moves_so_far);
+                    if fewest_moves.len() > max_states {
+                        return Err(PlanError::TooManyStates(max_states));
+                    }
+
+                    let lower_bound = get_lower_bound(&stacks, target);
+                    queue.push(Reverse((
+                        moves_so_far + lower_bound,
+                        lower_bound,
+                        nodes.len(),
+                    )));
+                    nodes.push(Node {
+                        stacks,
+                        moves_so_far,
+                        parent: Some((index, crate_move)),
+                    });
+                }
+            }
+        }
+    }
+
+    Err(PlanError::Unreachable)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crane::{CrateMover9000, CrateMover9001, LimitedCrane};
+    use crate::get_drawing_and_moves;
+    use crate::random::Random;
+    use std::collections::VecDeque;
+
+    fn apply_all(start: &[Vec<char>], moves: &[Move], crane: &dyn Crane) -> Vec<Vec<char>> {
+        let mut stacks = start.to_vec();
+        for crate_move in moves {
+            assert!(crate_move.count <= stacks[crate_move.from - 1].len());
+            crane.apply(&mut stacks, crate_move);
+        }
+        stacks
+    }
+
+    // The fewest moves by breadth first search, to check A* against
+    fn get_fewest_moves(start: &[Vec<char>], target: &Target, crane: &dyn Crane) -> usize {
+        let mut seen: HashSet<Vec<Vec<char>>> = HashSet::from([start.to_vec()]);
+        let mut queue = VecDeque::from([(start.to_vec(), 0)]);
+        while let Some((stacks, moves)) = queue.pop_front() {
+            if is_reached(&stacks, target) {
+                return moves;
+            }
+            for from in 1..=stacks.len() {
+                for to in (1..=stacks.len()).filter(|to| *to != from) {
+                    for count in 1..=stacks[from - 1].len() {
+                        let mut next = stacks.clone();
+                        crane.apply(&mut next, &Move { count, from, to });
+                        if seen.insert(next.clone()) {
+                            queue.push_back((next, moves + 1));
+                        }
+                    }
+                }
+            }
+        }
+        panic!("The target should be reachable");
+    }
+
+    #[test]
+    fn it_plans_at_most_as_many_moves_as_the_test_procedure() {
+        let (drawing, moves) = get_drawing_and_moves("./test.txt");
+
+        for crane in [&CrateMover9000 as &dyn Crane, &CrateMover9001] {
+            let end = apply_all(&drawing.stacks, &moves, crane);
+            let target = Target::Stacks(end.clone());
+            let plan = plan(&drawing.stacks, &target, crane, 100_000).unwrap();
+
+            assert!(plan.len() <= moves.len());
+            assert_eq!(apply_all(&drawing.stacks, &plan, crane), end);
+        }
+
+        let plan = plan(
+            &drawing.stacks,
+            &Target::TopLayer("CMZ".to_string()),
+            &CrateMover9000,
+            100_000,
+        )
+        .unwrap();
+        assert_eq!(
+            get_top_layer_of_stacks(&apply_all(&drawing.stacks, &plan, &CrateMover9000)),
+            "CMZ"
+        );
+    }
+
+    #[test]
+    fn it_finds_shortest_plans_for_random_arrangements() {
+        let mut random = Random(0x2022_1205);
+        let cranes: [&dyn Crane; 3] = [
+            &CrateMover9000,
+            &CrateMover9001,
+            &LimitedCrane { capacity: 2 },
+        ];
+
+        for _ in 0..30 {
+            let number_of_stacks = 2 + random.below(3);
+            let mut start: Vec<Vec<char>> = vec![Vec::new(); number_of_stacks];
+            for letter in "ABCDE".chars().take(2 + random.below(4)) {
+                start[random.below(number_of_stacks)].push(letter);
+            }
+            let crane = cranes[random.below(cranes.len())];
+
+            // Shuffle with random moves, so the target is always reachable
+            let mut end = start.clone();
+            for _ in 0..6 {
+                let from = 1 + random.below(number_of_stacks);
+                let to = 1 + random.below(number_of_stacks);
+                let count = random.below(end[from - 1].len() + 1);
+                crane.apply(&mut end, &Move { count, from, to });
+            }
+
+            for target in [
+                Target::Stacks(end.clone()),
+                Target::TopLayer(get_top_layer_of_stacks(&end)),
+            ] {
+                let plan = plan(&start, &target, crane, 1_000_000).unwrap();
+                assert!(is_reached(&apply_all(&start, &plan, crane), &target));
+                assert_eq!(plan.len(), get_fewest_moves(&start, &target, crane));
+            }
+        }
+    }
+
+    #[test]
+    fn it_reports_targets_it_cannot_reach() {
+        let start = vec![vec!['A', 'B'], vec![]];
+
+        assert_eq!(
+            plan(
+                &start,
+                &Target::Stacks(vec![vec!['A', 'B']]),
+                &CrateMover9000,
+                100
+            ),
+            Err(PlanError::StackCountMismatch {
+                start: 2,
+                target: 1
+            })
+        );
+        assert_eq!(
+            plan(
+                &start,
+                &Target::Stacks(vec![vec!['A'], vec!['C']]),
+                &CrateMover9000,
+                100
+            ),
+            Err(PlanError::DifferentCrates)
+        );
+        assert_eq!(
+            plan(
+                &start,
+                &Target::TopLayer("CA".to_string()),
+                &CrateMover9000,
+                100
+            ),
+            Err(PlanError::Unreachable)
+        );
+        assert_eq!(
+            plan(
+                &start,
+                &Target::TopLayer("AA".to_string()),
+                &CrateMover9001,
+                1
+            ),
+            Err(PlanError::TooManyStates(1))
+        );
+    }
+}
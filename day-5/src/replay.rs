@@ -0,0 +1,183 @@
+use crate::command::Move;
+use crate::crane::Crane;
+use crate::drawing::Drawing;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+// The stacks are saved every so many moves, so going back only has to redo a few of them
+const CHECKPOINT_INTERVAL: usize = 64;
+
+pub struct Replay<'a> {
+    crane: &'a dyn Crane,
+    moves: Vec<Move>,
+    labels: Vec<String>,
+    // The stacks before move number index * CHECKPOINT_INTERVAL + 1
+    checkpoints: Vec<Vec<Vec<char>>>,
+    stacks: Vec<Vec<char>>,
+    step: usize,
+}
+
+impl<'a> Replay<'a> {
+    pub fn new(drawing: Drawing, moves: Vec<Move>, crane: &'a dyn Crane) -> Replay<'a> {
+        Replay {
+            crane,
+            moves,
+            labels: drawing.labels,
+            checkpoints: vec![drawing.stacks.clone()],
+            stacks: drawing.stacks,
+            step: 0,
+        }
+    }
+
+    // How many moves have been made, so 0 is the starting drawing
+    pub fn step(&self) -> usize {
+        self.step
+    }
+
+    pub fn number_of_moves(&self) -> usize {
+        self.moves.len()
+    }
+
+    pub fn drawing(&self) -> Drawing {
+        Drawing {
+            labels: self.labels.clone(),
+            stacks: self.stacks.clone(),
+        }
+    }
+
+    // Returns false when there are no moves left
+    pub fn forward(&mut self) -> bool {
+        if self.step == self.moves.len() {
+            return false;
+        }
+        self.crane.apply(&mut self.stacks, &self.moves[self.step]);
+        self.step += 1;
+
+        if self.step.is_multiple_of(CHECKPOINT_INTERVAL)
+            && self.checkpoints.len() == self.step / CHECKPOINT_INTERVAL
+        {
+            self.checkpoints.push(self.stacks.clone());
+        }
+        true
+    }
+
+    // Returns false when already at the start
+    pub fn back(&mut self) -> bool {
+        if self.step == 0 {
+            return false;
+        }
+        self.jump_to(self.step - 1);
+        true
+    }
+
+    // Jumping past the last move stops after it
+    pub fn jump_to(&mut self, step: usize) {
+        let step = step.min(self.moves.len());
+        if step < self.step {
+            // Every checkpoint up to the current step has been saved on the way here
+            let checkpoint = step / CHECKPOINT_INTERVAL;
+            self.stacks = self.checkpoints[checkpoint].clone();
+            self.step = checkpoint * CHECKPOINT_INTERVAL;
+        }
+        while self.step < step {
+            self.forward();
+        }
+    }
+
+    // What the last move was, followed by the drawing after it
+    pub fn frame(&self) -> String {
+        let heading = match self.step {
+            0 => format!("Start, {} moves to go", self.moves.len()),
+            step => format!(
+                "After move {} of {}: {}",
+                step,
+                self.moves.len(),
+                self.moves[step - 1].with_labels(&self.labels)
+            ),
+        };
+        format!("{}\n{}\n", heading, self.drawing())
+    }
+
+    // Writes every frame from the start, separated by blank lines, and comes back to the
+    // current step afterwards
+    pub fn export(&mut self, file_path: &str) -> io::Result<()> {
+        let current_step = self.step;
+        let mut file = BufWriter::new(File::create(file_path)?);
+
+        self.jump_to(0);
+        write!(file, "{}", self.frame())?;
+        while self.forward() {
+            write!(file, "\n{}", self.frame())?;
+        }
+        self.jump_to(current_step);
+
+        file.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crane::{CrateMover9000, CrateMover9001};
+    use crate::drawing::parse_drawing;
+    use crate::get_drawing_and_moves;
+    use std::{env, fs};
+
+    fn get_states(drawing: &Drawing, moves: &[Move], crane: &dyn Crane) -> Vec<Vec<Vec<char>>> {
+        let mut stacks = drawing.stacks.clone();
+        let mut states = vec![stacks.clone()];
+        for crate_move in moves {
+            crane.apply(&mut stacks, crate_move);
+            states.push(stacks.clone());
+        }
+        states
+    }
+
+    #[test]
+    fn it_steps_and_jumps_to_the_same_states_as_a_straight_run() {
+        let (drawing, moves) = get_drawing_and_moves("./input.txt");
+
+        for crane in [&CrateMover9000 as &dyn Crane, &CrateMover9001] {
+            let states = get_states(&drawing, &moves, crane);
+            let mut replay = Replay::new(drawing.clone(), moves.clone(), crane);
+
+            while replay.forward() {
+                assert_eq!(replay.drawing().stacks, states[replay.step()]);
+            }
+            assert_eq!(replay.step(), replay.number_of_moves());
+            while replay.back() {
+                assert_eq!(replay.drawing().stacks, states[replay.step()]);
+            }
+            assert_eq!(replay.step(), 0);
+
+            for step in [300, 5, 64, 63, 128, 500, 1, 0, 10_000, 129] {
+                replay.jump_to(step);
+                let step = step.min(moves.len());
+                assert_eq!(replay.step(), step);
+                assert_eq!(replay.drawing().stacks, states[step]);
+            }
+        }
+    }
+
+    #[test]
+    fn it_exports_every_frame_as_a_drawing_that_parses_back() {
+        let (drawing, moves) = get_drawing_and_moves("./test.txt");
+        let states = get_states(&drawing, &moves, &CrateMover9000);
+        let mut replay = Replay::new(drawing, moves, &CrateMover9000);
+        replay.jump_to(2);
+
+        let file_path = env::temp_dir().join(format!("day-5-replay-{}.txt", std::process::id()));
+        replay.export(file_path.to_str().unwrap()).unwrap();
+        let file_contents = fs::read_to_string(&file_path).unwrap();
+        fs::remove_file(&file_path).unwrap();
+
+        assert_eq!(replay.step(), 2);
+        let frames: Vec<&str> = file_contents.split("\n\n").collect();
+        assert_eq!(frames.len(), states.len());
+        assert!(frames[1].starts_with("After move 1 of 4: move 1 from 2 to 1\n"));
+        for (frame, stacks) in frames.iter().zip(&states) {
+            let lines: Vec<&str> = frame.lines().skip(1).collect();
+            assert_eq!(parse_drawing(&lines).unwrap().stacks, *stacks);
+        }
+    }
+}
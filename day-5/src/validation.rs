@@ -0,0 +1,199 @@
+use crate::command::Move;
+use crate::crane::Crane;
+use crate::drawing::Drawing;
+use std::fmt;
+
+// What to do with a move that asks for more crates than its stack has
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum OnIllegalMove {
+    Skip,
+    Clamp,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Problem {
+    // The labels are the ones the stacks have in the drawing
+    IllegalMove {
+        line_number: usize,
+        crate_move: Move,
+        from_label: String,
+        to_label: String,
+        available: usize,
+    },
+    EmptyStack {
+        label: String,
+    },
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Problem::IllegalMove {
+                line_number,
+                crate_move,
+                from_label,
+                to_label,
+                available,
+            } => write!(
+                f,
+                "Line {}: move {} from {} to {} asks for {} crates but stack {} only has {}",
+                line_number,
+                crate_move.count,
+                from_label,
+                to_label,
+                crate_move.count,
+                from_label,
+                available
+            ),
+            Problem::EmptyStack { label } => write!(f, "Stack {} ends up empty", label),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct DryRun {
+    pub stacks: Vec<Vec<char>>,
+    pub problems: Vec<Problem>,
+}
+
+// Runs every move without ever panicking, so all the problems with a procedure are found in one
+// go instead of stopping at the first. Illegal moves are either left out or cut down to the
+// crates that are there, and the run carries on either way
+pub fn dry_run(
+    drawing: &Drawing,
+    moves: &[(usize, Move)],
+    crane: &dyn Crane,
+    on_illegal_move: OnIllegalMove,
+) -> DryRun {
+    let mut stacks = drawing.stacks.clone();
+    let mut problems: Vec<Problem> = Vec::new();
+
+    for (line_number, crate_move) in moves {
+        let available = stacks[crate_move.from - 1].len();
+        if crate_move.count <= available {
+            crane.apply(&mut stacks, crate_move);
+            continue;
+        }
+
+        problems.push(Problem::IllegalMove {
+            line_number: *line_number,
+            crate_move: *crate_move,
+            from_label: drawing.labels[crate_move.from - 1].clone(),
+            to_label: drawing.labels[crate_move.to - 1].clone(),
+            available,
+        });
+        if on_illegal_move == OnIllegalMove::Clamp {
+            let clamped_move = Move {
+                count: available,
+                ..*crate_move
+            };
+            crane.apply(&mut stacks, &clamped_move);
+        }
+    }
+
+    problems.extend(
+        drawing
+            .labels
+            .iter()
+            .zip(&stacks)
+            .filter(|(_, stack)| stack.is_empty())
+            .map(|(label, _)| Problem::EmptyStack {
+                label: label.clone(),
+            }),
+    );
+
+    DryRun { stacks, problems }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::parse_numbered_moves;
+    use crate::crane::{CrateMover9000, CrateMover9001};
+    use crate::drawing::parse_drawing;
+    use crate::{get_drawing_and_numbered_moves, get_top_layer_of_stacks};
+
+    #[test]
+    fn it_finds_no_problems_in_the_puzzle_files() {
+        for file_path in ["./test.txt", "./input.txt"] {
+            let (drawing, moves) = get_drawing_and_numbered_moves(file_path);
+            for crane in [&CrateMover9000 as &dyn Crane, &CrateMover9001] {
+                let dry_run = dry_run(&drawing, &moves, crane, OnIllegalMove::Skip);
+                assert_eq!(dry_run.problems, []);
+            }
+        }
+    }
+
+    #[test]
+    fn it_reports_every_illegal_move_and_empty_stack() {
+        let drawing = parse_drawing(&["[A]", "[B] [C]", " 1   2   3"]).unwrap();
+        let moves = parse_numbered_moves(
+            [
+                "move 3 from 1 to 2",
+                "move 1 from 3 to 1",
+                "",
+                "move 1 from 2 to 3",
+            ]
+            .into_iter(),
+            5,
+            &drawing.labels,
+        )
+        .unwrap();
+
+        let skipped = dry_run(&drawing, &moves, &CrateMover9000, OnIllegalMove::Skip);
+        assert_eq!(
+            skipped.problems,
+            [
+                Problem::IllegalMove {
+                    line_number: 5,
+                    crate_move: moves[0].1,
+                    from_label: "1".to_string(),
+                    to_label: "2".to_string(),
+                    available: 2
+                },
+                Problem::IllegalMove {
+                    line_number: 6,
+                    crate_move: moves[1].1,
+                    from_label: "3".to_string(),
+                    to_label: "1".to_string(),
+                    available: 0
+                },
+                Problem::EmptyStack {
+                    label: "2".to_string()
+                },
+            ]
+        );
+        assert_eq!(skipped.stacks, [vec!['B', 'A'], vec![], vec!['C']]);
+        assert_eq!(
+            skipped.problems[0].to_string(),
+            "Line 5: move 3 from 1 to 2 asks for 3 crates but stack 1 only has 2"
+        );
+        assert_eq!(get_top_layer_of_stacks(&skipped.stacks), "A-C");
+
+        let clamped = dry_run(&drawing, &moves, &CrateMover9000, OnIllegalMove::Clamp);
+        assert_eq!(clamped.problems.len(), 3);
+        assert_eq!(clamped.stacks, [vec![], vec!['C', 'A'], vec!['B']]);
+        assert_eq!(clamped.problems[2].to_string(), "Stack 1 ends up empty");
+        assert_eq!(get_top_layer_of_stacks(&clamped.stacks), "-AB");
+    }
+
+    #[test]
+    fn it_names_stacks_by_their_labels() {
+        let drawing = parse_drawing(&["[A]", "[B] [C]", " 1   3   7"]).unwrap();
+        let moves =
+            parse_numbered_moves(["move 2 from 7 to 3"].into_iter(), 5, &drawing.labels).unwrap();
+
+        let dry_run = dry_run(&drawing, &moves, &CrateMover9000, OnIllegalMove::Skip);
+        assert_eq!(
+            dry_run
+                .problems
+                .iter()
+                .map(Problem::to_string)
+                .collect::<Vec<String>>(),
+            [
+                "Line 5: move 2 from 7 to 3 asks for 2 crates but stack 7 only has 0",
+                "Stack 7 ends up empty"
+            ]
+        );
+    }
+}
@@ -0,0 +1,53 @@
+use std::collections::HashMap;
+
+// A number for every symbol, 0 until it's changed
+pub trait SymbolTable<T>: Default {
+    fn get(&self, symbol: T) -> u64;
+    fn get_mut(&mut self, symbol: T) -> &mut u64;
+}
+
+// Something a datastream can be made of, along with how to keep a number for each one
+pub trait Symbol: Copy {
+    type Table: SymbolTable<Self>;
+}
+
+// There are few enough bytes to keep a number for every one of them
+pub struct ByteTable([u64; 256]);
+
+impl Default for ByteTable {
+    fn default() -> ByteTable {
+        ByteTable([0; 256])
+    }
+}
+
+impl SymbolTable<u8> for ByteTable {
+    fn get(&self, symbol: u8) -> u64 {
+        self.0[symbol as usize]
+    }
+
+    fn get_mut(&mut self, symbol: u8) -> &mut u64 {
+        &mut self.0[symbol as usize]
+    }
+}
+
+impl Symbol for u8 {
+    type Table = ByteTable;
+}
+
+// Unicode has over a million scalar values, so only the ones that turn up are kept
+#[derive(Default)]
+pub struct CharTable(HashMap<char, u64>);
+
+impl SymbolTable<char> for CharTable {
+    fn get(&self, symbol: char) -> u64 {
+        self.0.get(&symbol).copied().unwrap_or(0)
+    }
+
+    fn get_mut(&mut self, symbol: char) -> &mut u64 {
+        self.0.entry(symbol).or_insert(0)
+    }
+}
+
+impl Symbol for char {
+    type Table = CharTable;
+}
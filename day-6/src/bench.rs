@@ -0,0 +1,104 @@
+use crate::parallel::find_marker_in_parallel;
+use crate::stream::find_marker;
+use std::io::{self, Read};
+use std::time::Instant;
+
+const STREAM_LENGTH: u64 = 4 * 1024 * 1024 * 1024;
+const IN_MEMORY_LENGTH: u64 = 1024 * 1024 * 1024;
+const CHUNK_SIZE: usize = 1024 * 1024;
+const CYCLE: &[u8] = b"abcdefghijklm";
+
+// Repeats the same 13 letters, so there's never a start-of-message marker, until length bytes
+// in, where a new letter completes one. Made up as it's read, so it takes no memory
+struct GeneratedStream {
+    length: u64,
+    position: u64,
+}
+
+impl Read for GeneratedStream {
+    fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        let mut cycle_position = (self.position % CYCLE.len() as u64) as usize;
+        for byte in buffer.iter_mut() {
+            *byte = if self.position < self.length {
+                CYCLE[cycle_position]
+            } else {
+                b'n'
+            };
+            self.position += 1;
+            cycle_position = if cycle_position + 1 == CYCLE.len() {
+                0
+            } else {
+                cycle_position + 1
+            };
+        }
+        Ok(buffer.len())
+    }
+}
+
+fn generate_stream(length: u64) -> GeneratedStream {
+    GeneratedStream {
+        length,
+        position: 0,
+    }
+}
+
+fn time<T>(description: &str, run: impl Fn() -> T) -> T {
+    let start = Instant::now();
+    let result = run();
+    println!("{}: {:?}", description, start.elapsed());
+    result
+}
+
+// Searches the same gigabyte held in memory one byte after another and in parallel chunks, with
+// the marker at the end and then a third of the way in
+fn compare_with_parallel_search() {
+    println!(
+        "Searching {} bytes in chunks of {} on {} threads",
+        IN_MEMORY_LENGTH,
+        CHUNK_SIZE,
+        rayon::current_num_threads()
+    );
+
+    for marker_at in [IN_MEMORY_LENGTH - 1, IN_MEMORY_LENGTH / 3] {
+        let mut bytes = vec![0; IN_MEMORY_LENGTH as usize];
+        generate_stream(marker_at).read_exact(&mut bytes).unwrap();
+
+        let sequential = time("Sequential", || find_marker(&bytes[..], 14).unwrap());
+        let parallel = time("Parallel", || {
+            find_marker_in_parallel(&bytes, 14, CHUNK_SIZE)
+        });
+        assert_eq!(sequential, Some(marker_at + 1));
+        assert_eq!(parallel, sequential);
+    }
+}
+
+pub fn run_benchmarks() {
+    compare_with_parallel_search();
+
+    let start = Instant::now();
+    let marker = find_marker(generate_stream(STREAM_LENGTH), 14).unwrap();
+    let elapsed = start.elapsed();
+
+    assert_eq!(marker, Some(STREAM_LENGTH + 1));
+    println!(
+        "Found the marker after {} bytes in {:?}, {:.0} MB/s",
+        STREAM_LENGTH + 1,
+        elapsed,
+        STREAM_LENGTH as f64 / elapsed.as_secs_f64() / 1e6
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_finds_the_marker_at_the_end_of_a_long_generated_stream() {
+        for length in [13, 14, 64 * 1024 - 1, 64 * 1024, 16 * 1024 * 1024 + 5] {
+            assert_eq!(
+                find_marker(generate_stream(length), 14).unwrap(),
+                Some(length + 1)
+            );
+        }
+    }
+}
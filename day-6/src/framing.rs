@@ -0,0 +1,274 @@
+use crate::stream::MarkerDetector;
+use std::fmt;
+
+const PACKET_MARKER_LENGTH: usize = 4;
+const MESSAGE_MARKER_LENGTH: usize = 14;
+
+const PACKET_MARKER: &[u8; PACKET_MARKER_LENGTH] = b"pckt";
+// Message markers are made from these, leaving out whichever the payload ends with
+const MESSAGE_MARKER_BYTES: &[u8] = b"abcdefghijklmno";
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FrameEnd {
+    // A start-of-message marker came right after the payload
+    Message,
+    // The payload reached the longest a frame may be
+    Length,
+    // The stream ended first
+    Truncated,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Frame {
+    // Where the start-of-packet marker begins, counting from 0
+    pub packet_offset: u64,
+    pub payload_offset: u64,
+    pub payload: Vec<u8>,
+    pub end: FrameEnd,
+}
+
+// Splits a stream into frames. Each frame starts at the first start-of-packet marker after the
+// one before, and its payload is what follows up to the next start-of-message marker. At most
+// max_length bytes are read after the packet marker, so a payload ending at a message marker is
+// at most max_length - MESSAGE_MARKER_LENGTH bytes long
+pub struct Frames<I> {
+    bytes: I,
+    position: u64,
+    max_length: usize,
+}
+
+pub fn get_frames<I: Iterator<Item = u8>>(
+    bytes: impl IntoIterator<IntoIter = I>,
+    max_length: usize,
+) -> Frames<I> {
+    Frames {
+        bytes: bytes.into_iter(),
+        position: 0,
+        max_length,
+    }
+}
+
+impl<I: Iterator<Item = u8>> Iterator for Frames<I> {
+    type Item = Frame;
+
+    fn next(&mut self) -> Option<Frame> {
+        // Anything before the packet marker is noise and skipped
+        let mut packet_detector = MarkerDetector::new(PACKET_MARKER_LENGTH);
+        loop {
+            let byte = self.bytes.next()?;
+            self.position += 1;
+            if packet_detector.push(byte) {
+                break;
+            }
+        }
+        let payload_offset = self.position;
+
+        let mut message_detector = MarkerDetector::new(MESSAGE_MARKER_LENGTH);
+        let mut payload: Vec<u8> = Vec::new();
+        let end = loop {
+            if payload.len() == self.max_length {
+                break FrameEnd::Length;
+            }
+            let Some(byte) = self.bytes.next() else {
+                break FrameEnd::Truncated;
+            };
+            self.position += 1;
+            payload.push(byte);
+            if message_detector.push(byte) {
+                payload.truncate(payload.len() - MESSAGE_MARKER_LENGTH);
+                break FrameEnd::Message;
+            }
+        };
+
+        Some(Frame {
+            packet_offset: payload_offset - PACKET_MARKER_LENGTH as u64,
+            payload_offset,
+            payload,
+            end,
+        })
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum EncodeError {
+    // The payload has a message marker of its own, which would end it early
+    ContainsMarker { payload: usize, offset: usize },
+    TooLong { payload: usize, length: usize },
+}
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EncodeError::ContainsMarker { payload, offset } => write!(
+                f,
+                "Payload {} has a start-of-message marker ending at byte {}",
+                payload, offset
+            ),
+            EncodeError::TooLong { payload, length } => write!(
+                f,
+                "Payload {} is {} bytes, too long to fit in a frame",
+                payload, length
+            ),
+        }
+    }
+}
+
+// Starts with the last byte of the payload, so no window that takes in the end of the payload
+// and the start of the marker is all different, and the marker is the first one found
+fn get_message_marker(payload: &[u8]) -> Vec<u8> {
+    match payload.last() {
+        Some(last) => std::iter::once(*last)
+            .chain(
+                MESSAGE_MARKER_BYTES
+                    .iter()
+                    .copied()
+                    .filter(|byte| byte != last),
+            )
+            .take(MESSAGE_MARKER_LENGTH)
+            .collect(),
+        None => MESSAGE_MARKER_BYTES[..MESSAGE_MARKER_LENGTH].to_vec(),
+    }
+}
+
+// Frames each payload between a packet marker and a message marker, so that get_frames gives
+// back the same payloads
+pub fn encode(payloads: &[&[u8]], max_length: usize) -> Result<Vec<u8>, EncodeError> {
+    let mut stream: Vec<u8> = Vec::new();
+
+    for (index, payload) in payloads.iter().enumerate() {
+        if payload.len() + MESSAGE_MARKER_LENGTH > max_length {
+            return Err(EncodeError::TooLong {
+                payload: index,
+                length: payload.len(),
+            });
+        }
+        let mut detector = MarkerDetector::new(MESSAGE_MARKER_LENGTH);
+        if let Some(offset) = payload.iter().position(|byte| detector.push(*byte)) {
+            return Err(EncodeError::ContainsMarker {
+                payload: index,
+                offset: offset + 1,
+            });
+        }
+
+        stream.extend_from_slice(PACKET_MARKER);
+        stream.extend_from_slice(payload);
+        stream.extend(get_message_marker(payload));
+    }
+
+    Ok(stream)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    // Any bytes at all, but never more than 13 different ones, so never a message marker
+    fn any_payloads() -> impl Strategy<Value = Vec<Vec<u8>>> {
+        let payload = (
+            prop::array::uniform13(any::<u8>()),
+            prop::collection::vec(0..13usize, 0..100),
+        )
+            .prop_map(|(alphabet, letters)| {
+                letters.iter().map(|letter| alphabet[*letter]).collect()
+            });
+        prop::collection::vec(payload, 1..=20)
+    }
+
+    proptest! {
+        #[test]
+        fn it_decodes_what_it_encodes(payloads in any_payloads()) {
+            let payload_slices: Vec<&[u8]> = payloads.iter().map(Vec::as_slice).collect();
+            let stream = encode(&payload_slices, 200).unwrap();
+
+            let frames: Vec<Frame> = get_frames(stream.iter().copied(), 200).collect();
+            prop_assert_eq!(frames.len(), payloads.len());
+            for (frame, payload) in frames.iter().zip(&payloads) {
+                prop_assert_eq!(&frame.payload, payload);
+                prop_assert_eq!(frame.end, FrameEnd::Message);
+                prop_assert_eq!(
+                    &stream[frame.packet_offset as usize..frame.payload_offset as usize],
+                    PACKET_MARKER
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn it_skips_noise_and_cuts_long_payloads() {
+        let stream = b"aaaabcd0101010101010101wxyzabcdefghijklmnzz";
+        let frames: Vec<Frame> = get_frames(stream.iter().copied(), 16).collect();
+
+        assert_eq!(
+            frames,
+            [
+                Frame {
+                    packet_offset: 3,
+                    payload_offset: 7,
+                    payload: b"0101010101010101".to_vec(),
+                    end: FrameEnd::Length
+                },
+                Frame {
+                    packet_offset: 23,
+                    payload_offset: 27,
+                    payload: b"".to_vec(),
+                    end: FrameEnd::Message
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn it_reports_the_frame_a_stream_is_cut_off_in() {
+        let payloads: [&[u8]; 2] = [b"hello", b"world"];
+        let stream = encode(&payloads, 100).unwrap();
+        let second_frame = PACKET_MARKER_LENGTH + 5 + MESSAGE_MARKER_LENGTH;
+
+        for length in 0..stream.len() {
+            let frames: Vec<Frame> = get_frames(stream[..length].iter().copied(), 100).collect();
+            let complete =
+                usize::from(length >= second_frame) + usize::from(length == stream.len());
+            let started = usize::from(length >= PACKET_MARKER_LENGTH)
+                + usize::from(length >= second_frame + PACKET_MARKER_LENGTH);
+
+            assert_eq!(frames.len(), started);
+            for (index, frame) in frames.iter().enumerate() {
+                if index < complete {
+                    assert_eq!(frame.payload, payloads[index]);
+                } else {
+                    assert_eq!(frame.end, FrameEnd::Truncated);
+                    let payload_offset = frame.payload_offset as usize;
+                    assert_eq!(frame.payload, &stream[payload_offset..length]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn it_refuses_payloads_that_would_not_come_back_the_same() {
+        assert_eq!(
+            encode(&[b"ok", b"abcdefghijklmnop"], 100),
+            Err(EncodeError::ContainsMarker {
+                payload: 1,
+                offset: 14
+            })
+        );
+        assert_eq!(
+            encode(&[&[b'x'; 90]], 100),
+            Err(EncodeError::TooLong {
+                payload: 0,
+                length: 90
+            })
+        );
+    }
+
+    #[test]
+    fn it_finds_the_first_packet_in_the_input_file() {
+        let file_contents = std::fs::read("./input.txt").unwrap();
+        let frame = get_frames(file_contents, 4096).next().unwrap();
+
+        assert_eq!(frame.payload_offset, 1794);
+        assert_eq!(frame.end, FrameEnd::Message);
+        assert_eq!(frame.payload_offset + frame.payload.len() as u64, 2851 - 14);
+    }
+}
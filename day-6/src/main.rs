@@ -1,68 +1,417 @@
-use std::fs;
+mod alphabet;
+mod bench;
+mod framing;
+mod parallel;
+mod scan;
+mod stream;
 
-fn get_marker_from_unique_characters(line: &str, unique_character_marker: usize) -> usize {
-    let mut unique_characters: Vec<char> = vec![];
-    let mut marker = 0;
+use framing::{encode, get_frames};
+use parallel::find_marker_in_parallel;
+use scan::{get_distinct_lengths, scan, MarkerScan, Occurrences};
+use std::fs::File;
+use std::{env, fs, io};
+use stream::find_marker;
 
-    for (index, char) in line.chars().enumerate() {
-        if unique_characters.contains(&char) {
-            let index_of_unique_char = unique_characters
-                .iter()
-                .position(|&unique_char| unique_char == char)
-                .unwrap();
-            unique_characters.drain(0..=index_of_unique_char);
+const DEFAULT_CHUNK_SIZE: usize = 1024 * 1024;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Symbols {
+    // Any file, even one that isn't text
+    Bytes,
+    // Unicode scalar values, for UTF-8 text
+    Chars,
+}
+
+// How to read a file as datastreams
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct Reading {
+    symbols: Symbols,
+    // Each line is a datastream of its own, otherwise the whole file is one
+    per_line: bool,
+}
+
+// Lines end at a newline, without any carriage return before it, like str::lines
+fn get_streams(file_contents: &[u8], per_line: bool) -> Vec<&[u8]> {
+    if !per_line {
+        return vec![file_contents];
+    }
+    let mut lines: Vec<&[u8]> = file_contents
+        .split(|byte| *byte == b'\n')
+        .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+        .collect();
+    if file_contents.is_empty() || file_contents.ends_with(b"\n") {
+        lines.pop();
+    }
+    lines
+}
+
+// Finds the markers of every size in one pass over each datastream
+fn scan_file(
+    file_path: &str,
+    unique_character_markers: &[usize],
+    occurrences: Occurrences,
+    reading: Reading,
+) -> Vec<Vec<MarkerScan>> {
+    let file_contents = fs::read(file_path).expect("Should have been able to read the file");
+
+    get_streams(&file_contents, reading.per_line)
+        .into_iter()
+        .map(|stream| match reading.symbols {
+            Symbols::Bytes => scan(
+                stream.iter().copied(),
+                unique_character_markers,
+                occurrences,
+            ),
+            Symbols::Chars => scan(
+                std::str::from_utf8(stream)
+                    .expect("Should be UTF-8 to be read as characters")
+                    .chars(),
+                unique_character_markers,
+                occurrences,
+            ),
+        })
+        .collect()
+}
+
+// The first marker of each size on every line, as the puzzle counts them
+fn get_marker_characters(file_path: &str, unique_character_markers: &[usize]) -> Vec<Vec<usize>> {
+    let reading = Reading {
+        symbols: Symbols::Chars,
+        per_line: true,
+    };
+    let mut marker_characters: Vec<Vec<usize>> = vec![Vec::new(); unique_character_markers.len()];
+
+    for scans in scan_file(
+        file_path,
+        unique_character_markers,
+        Occurrences::First,
+        reading,
+    ) {
+        for (markers, scan) in marker_characters.iter_mut().zip(scans) {
+            markers.push(scan.markers.first().map_or(0, |marker| *marker as usize));
         }
+    }
+
+    marker_characters
+}
 
-        unique_characters.push(char);
+fn print_scan(
+    file_path: &str,
+    unique_character_markers: &[usize],
+    occurrences: Occurrences,
+    reading: Reading,
+) {
+    let streams = scan_file(file_path, unique_character_markers, occurrences, reading);
 
-        if unique_characters.len() == unique_character_marker {
-            marker = index + 1;
-            break;
+    for (index, scans) in streams.iter().enumerate() {
+        if streams.len() > 1 {
+            println!("Line {}", index + 1);
+        }
+        for scan in scans {
+            let markers: Vec<String> = scan.markers.iter().map(u64::to_string).collect();
+            println!("{}: {}", scan.window_size, markers.join(" "));
         }
     }
+}
 
-    return marker;
+fn parse_reading(symbols: &str, streams: &str) -> Reading {
+    Reading {
+        symbols: match symbols {
+            "bytes" => Symbols::Bytes,
+            "chars" => Symbols::Chars,
+            _ => panic!("Should read either bytes or chars"),
+        },
+        per_line: match streams {
+            "lines" => true,
+            "file" => false,
+            _ => panic!("Should read either each line or the whole file as a datastream"),
+        },
+    }
 }
 
-fn get_marker_characters(file_path: &str, unique_character_marker: usize) -> Vec<usize> {
-    let file_contents =
-        fs::read_to_string(file_path).expect("Should have been able to read the file");
+fn print_frames(file_path: &str, max_length: usize) {
+    let file_contents = fs::read(file_path).expect("Should have been able to read the file");
 
-    return file_contents
-        .lines()
-        .map(|line| get_marker_from_unique_characters(line, unique_character_marker))
-        .collect();
+    for frame in get_frames(file_contents, max_length) {
+        println!(
+            "{} {} {:?} {:?}",
+            frame.packet_offset,
+            frame.payload_offset,
+            frame.end,
+            String::from_utf8_lossy(&frame.payload)
+        );
+    }
+}
+
+fn print_streamed_marker(reader: impl io::Read, unique_character_marker: usize) {
+    match find_marker(reader, unique_character_marker) {
+        Ok(Some(marker)) => println!("{}", marker),
+        Ok(None) => println!("No marker"),
+        Err(error) => panic!("Should have been able to read the stream: {}", error),
+    }
 }
 
 fn main() {
-    get_marker_characters("./test.txt", 14);
+    let arguments: Vec<String> = env::args().skip(1).collect();
+
+    match arguments.iter().map(String::as_str).collect::<Vec<&str>>()[..] {
+        // cargo run --release -- bench
+        ["bench"] => bench::run_benchmarks(),
+        // cargo run -- stream <marker length> [file], reading stdin without a file
+        ["stream", unique_character_marker, ref file_path @ ..] => {
+            let unique_character_marker = unique_character_marker
+                .parse()
+                .expect("Marker length should be a number");
+            match file_path {
+                [] => print_streamed_marker(io::stdin().lock(), unique_character_marker),
+                [file_path] => print_streamed_marker(
+                    File::open(file_path).expect("Should have been able to open the file"),
+                    unique_character_marker,
+                ),
+                _ => panic!("Only one file can be streamed at a time"),
+            }
+        }
+        // cargo run -- scan <first|every> <bytes|chars> <lines|file> <file> <marker length>...
+        ["scan", occurrences, symbols, streams, file_path, ref unique_character_markers @ ..] => {
+            let occurrences = match occurrences {
+                "first" => Occurrences::First,
+                "every" => Occurrences::Every,
+                _ => panic!("Should look for either the first or every marker"),
+            };
+            let unique_character_markers: Vec<usize> = unique_character_markers
+                .iter()
+                .map(|marker| marker.parse().expect("Marker length should be a number"))
+                .collect();
+            print_scan(
+                file_path,
+                &unique_character_markers,
+                occurrences,
+                parse_reading(symbols, streams),
+            );
+        }
+        // cargo run --release -- parallel <marker length> <file> [chunk size]
+        ["parallel", unique_character_marker, file_path, ref chunk_size @ ..] => {
+            let file_contents =
+                fs::read(file_path).expect("Should have been able to read the file");
+            let chunk_size = match chunk_size {
+                [] => DEFAULT_CHUNK_SIZE,
+                [chunk_size] => chunk_size.parse().expect("Chunk size should be a number"),
+                _ => panic!("Only one chunk size can be given"),
+            };
+            let marker = find_marker_in_parallel(
+                &file_contents,
+                unique_character_marker
+                    .parse()
+                    .expect("Marker length should be a number"),
+                chunk_size,
+            );
+            match marker {
+                Some(marker) => println!("{}", marker),
+                None => println!("No marker"),
+            }
+        }
+        // cargo run -- frames <file> <max frame length>
+        ["frames", file_path, max_length] => {
+            print_frames(
+                file_path,
+                max_length.parse().expect("Frame length should be a number"),
+            );
+        }
+        // cargo run -- encode <output file> <max frame length> <payload>...
+        ["encode", output_file_path, max_length, ref payloads @ ..] => {
+            let payloads: Vec<&[u8]> = payloads.iter().map(|payload| payload.as_bytes()).collect();
+            let stream = encode(
+                &payloads,
+                max_length.parse().expect("Frame length should be a number"),
+            )
+            .unwrap_or_else(|error| panic!("{}", error));
+            fs::write(output_file_path, stream).expect("Should have been able to write the file");
+        }
+        // cargo run -- lengths <bytes|chars> <file>, reading the whole file as one datastream
+        ["lengths", symbols, file_path] => {
+            let file_contents =
+                fs::read(file_path).expect("Should have been able to read the file");
+            let lengths: Vec<u64> = match parse_reading(symbols, "file").symbols {
+                Symbols::Bytes => get_distinct_lengths(file_contents).collect(),
+                Symbols::Chars => get_distinct_lengths(
+                    String::from_utf8(file_contents)
+                        .expect("Should be UTF-8 to be read as characters")
+                        .chars(),
+                )
+                .collect(),
+            };
+            for (offset, length) in lengths.iter().enumerate() {
+                println!("{} {}", offset + 1, length);
+            }
+        }
+        _ => {
+            let marker_characters = get_marker_characters("./input.txt", &[4, 14]);
+            println!("Part 1: {:?}", marker_characters[0]);
+            println!("Part 2: {:?}", marker_characters[1]);
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::get_marker_characters;
+    use crate::scan::Occurrences;
+    use crate::{get_marker_characters, scan_file, Reading, Symbols};
+    use std::{env, fs};
+
+    fn write_temporary_file(name: &str, contents: &[u8]) -> String {
+        let file_path = env::temp_dir().join(format!("day-6-{}-{}", std::process::id(), name));
+        fs::write(&file_path, contents).unwrap();
+        file_path.to_str().unwrap().to_string()
+    }
+
+    fn get_first_markers(
+        file_path: &str,
+        unique_character_markers: &[usize],
+        reading: Reading,
+    ) -> Vec<Vec<Option<u64>>> {
+        scan_file(
+            file_path,
+            unique_character_markers,
+            Occurrences::First,
+            reading,
+        )
+        .into_iter()
+        .map(|scans| {
+            scans
+                .into_iter()
+                .map(|scan| scan.markers.first().copied())
+                .collect()
+        })
+        .collect()
+    }
+
+    const BYTES_PER_LINE: Reading = Reading {
+        symbols: Symbols::Bytes,
+        per_line: true,
+    };
+    const BYTES_IN_FILE: Reading = Reading {
+        symbols: Symbols::Bytes,
+        per_line: false,
+    };
+    const CHARS_PER_LINE: Reading = Reading {
+        symbols: Symbols::Chars,
+        per_line: true,
+    };
+    const CHARS_IN_FILE: Reading = Reading {
+        symbols: Symbols::Chars,
+        per_line: false,
+    };
 
     #[test]
     fn it_returns_expected_marker_characters_for_test_file() {
-        let marker_characters = get_marker_characters("./test.txt", 4);
-        assert_eq!(marker_characters, [7, 5, 6, 10, 11]);
+        let marker_characters = get_marker_characters("./test.txt", &[4]);
+        assert_eq!(marker_characters, [[7, 5, 6, 10, 11]]);
     }
 
     #[test]
     fn it_returns_expected_marker_characters_for_test_file_2() {
-        let marker_characters = get_marker_characters("./test2.txt", 14);
-        assert_eq!(marker_characters, [19, 23, 23, 29, 26]);
+        let marker_characters = get_marker_characters("./test2.txt", &[14]);
+        assert_eq!(marker_characters, [[19, 23, 23, 29, 26]]);
     }
 
     #[test]
     fn it_returns_expected_marker_characters_for_input_file_part_1() {
-        let marker_characters = get_marker_characters("./input.txt", 4);
-        assert_eq!(marker_characters, [1794]);
+        let marker_characters = get_marker_characters("./input.txt", &[4]);
+        assert_eq!(marker_characters, [[1794]]);
     }
 
     #[test]
     fn it_returns_expected_marker_characters_for_input_file_part_2() {
-        let marker_characters = get_marker_characters("./input.txt", 14);
-        assert_eq!(marker_characters, [2851]);
+        let marker_characters = get_marker_characters("./input.txt", &[14]);
+        assert_eq!(marker_characters, [[2851]]);
+    }
+
+    #[test]
+    fn it_returns_both_parts_from_one_pass_over_each_file() {
+        assert_eq!(
+            get_marker_characters("./test.txt", &[4, 14]),
+            [vec![7, 5, 6, 10, 11], vec![19, 23, 23, 29, 26]]
+        );
+        assert_eq!(
+            get_marker_characters("./input.txt", &[4, 14]),
+            [[1794], [2851]]
+        );
+    }
+
+    #[test]
+    fn it_reads_the_whole_file_as_one_datastream() {
+        assert_eq!(
+            get_first_markers("./test.txt", &[4, 14], BYTES_PER_LINE),
+            [
+                [Some(7), Some(19)],
+                [Some(5), Some(23)],
+                [Some(6), Some(23)],
+                [Some(10), Some(29)],
+                [Some(11), Some(26)],
+            ]
+        );
+        assert_eq!(
+            get_first_markers("./test.txt", &[4, 14], CHARS_IN_FILE),
+            [[Some(7), Some(19)]]
+        );
+
+        // Markers can run across the end of a line, newline included
+        let file_path = write_temporary_file("lines.txt", b"aab\ncdd\r\n");
+        assert_eq!(
+            get_first_markers(&file_path, &[3, 4], BYTES_PER_LINE),
+            [[None, None], [None, None]]
+        );
+        assert_eq!(
+            get_first_markers(&file_path, &[3, 4], BYTES_IN_FILE),
+            [[Some(4), Some(5)]]
+        );
+        fs::remove_file(file_path).unwrap();
+    }
+
+    #[test]
+    fn it_counts_characters_or_bytes_in_non_ascii_text() {
+        let file_path = write_temporary_file("accents.txt", "aéaébcd".as_bytes());
+        assert_eq!(
+            get_first_markers(&file_path, &[4], CHARS_PER_LINE),
+            [[Some(6)]]
+        );
+        assert_eq!(
+            get_first_markers(&file_path, &[4], BYTES_PER_LINE),
+            [[Some(7)]]
+        );
+        fs::remove_file(file_path).unwrap();
+
+        // More different characters than there are bytes
+        let text: String = (0..300)
+            .filter_map(|offset| char::from_u32(0x4e00 + offset))
+            .collect();
+        let file_path = write_temporary_file("ideographs.txt", text.as_bytes());
+        assert_eq!(
+            get_first_markers(&file_path, &[300, 301], CHARS_IN_FILE),
+            [[Some(300), None]]
+        );
+        assert_eq!(
+            get_first_markers(&file_path, &[257], BYTES_IN_FILE),
+            [[None]]
+        );
+        fs::remove_file(file_path).unwrap();
+    }
+
+    #[test]
+    fn it_finds_markers_in_binary_files() {
+        // Every byte value, most of which aren't valid UTF-8 on their own
+        let bytes: Vec<u8> = (0..=255).rev().chain(0..=255).collect();
+        let file_path = write_temporary_file("binary.bin", &bytes);
+
+        assert_eq!(
+            get_first_markers(&file_path, &[2, 256], BYTES_IN_FILE),
+            [[Some(2), Some(256)]]
+        );
+        // Only newline bytes split the file into lines
+        assert_eq!(
+            get_first_markers(&file_path, &[245, 246], BYTES_PER_LINE),
+            [[Some(245), None], [None, None], [Some(245), None]]
+        );
+        fs::remove_file(file_path).unwrap();
     }
 }
@@ -0,0 +1,104 @@
+use crate::alphabet::Symbol;
+use crate::stream::MarkerDetector;
+use rayon::prelude::*;
+
+// Looks for the marker in chunks of chunk_size symbols at once, on rayon's thread pool. Each
+// chunk also takes in the window_size - 1 symbols after it, so every window starts in exactly
+// one chunk and is seen whole there. Chunks are searched in any order, but the first chunk to
+// have a marker has the earliest one, and the chunks after it are abandoned once it's found
+pub fn find_marker_in_parallel<T: Symbol + Sync>(
+    symbols: &[T],
+    window_size: usize,
+    chunk_size: usize,
+) -> Option<u64> {
+    assert!(window_size > 0, "A marker needs at least one character");
+    assert!(chunk_size > 0, "Chunks need at least one symbol");
+    let number_of_chunks = symbols.len().div_ceil(chunk_size);
+
+    (0..number_of_chunks)
+        .into_par_iter()
+        .find_map_first(|chunk| {
+            let start = chunk * chunk_size;
+            // Saturating, as the chunk size can be anything up to usize::MAX
+            let end = start
+                .saturating_add(chunk_size)
+                .saturating_add(window_size - 1)
+                .min(symbols.len());
+            let mut detector = MarkerDetector::new(window_size);
+
+            symbols[start..end]
+                .iter()
+                .position(|symbol| detector.push(*symbol))
+                .map(|index| (start + index + 1) as u64)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stream::find_marker;
+    use proptest::prelude::*;
+
+    fn find_marker_in_sequence<T: Symbol>(symbols: &[T], window_size: usize) -> Option<u64> {
+        let mut detector = MarkerDetector::new(window_size);
+        symbols
+            .iter()
+            .position(|symbol| detector.push(*symbol))
+            .map(|index| index as u64 + 1)
+    }
+
+    #[test]
+    fn it_finds_the_same_markers_in_the_input_file() {
+        let file_contents = std::fs::read("./input.txt").unwrap();
+
+        for window_size in [4, 14] {
+            for chunk_size in [1, 3, 13, 14, 100, 4096, 10_000] {
+                assert_eq!(
+                    find_marker_in_parallel(&file_contents, window_size, chunk_size),
+                    find_marker(&file_contents[..], window_size).unwrap()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn it_takes_chunks_larger_than_the_input() {
+        let bytes = b"mjqjpqmgbljsphdztnvjfqwrcgsmlb";
+        for chunk_size in [bytes.len(), usize::MAX / 2, usize::MAX] {
+            assert_eq!(find_marker_in_parallel(bytes, 14, chunk_size), Some(19));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "A marker needs at least one character")]
+    fn it_rejects_markers_without_characters() {
+        find_marker_in_parallel::<u8>(&[], 0, 10);
+    }
+
+    proptest! {
+        #[test]
+        fn it_matches_the_sequential_search_on_bytes(
+            bytes in prop::collection::vec(0u8..16, 0..400),
+            window_size in 1usize..18,
+            chunk_size in 1usize..60,
+        ) {
+            prop_assert_eq!(
+                find_marker_in_parallel(&bytes, window_size, chunk_size),
+                find_marker_in_sequence(&bytes, window_size)
+            );
+        }
+
+        #[test]
+        fn it_matches_the_sequential_search_on_characters(
+            text in "[a-hé日🦀]{0,200}",
+            window_size in 1usize..12,
+            chunk_size in 1usize..40,
+        ) {
+            let symbols: Vec<char> = text.chars().collect();
+            prop_assert_eq!(
+                find_marker_in_parallel(&symbols, window_size, chunk_size),
+                find_marker_in_sequence(&symbols, window_size)
+            );
+        }
+    }
+}
@@ -0,0 +1,193 @@
+use crate::alphabet::{Symbol, SymbolTable};
+
+// Whether to stop at the first marker of each size or carry on to the end
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Occurrences {
+    First,
+    Every,
+}
+
+// The markers of one size, as how many symbols came up to and including each
+#[derive(Debug, PartialEq, Eq)]
+pub struct MarkerScan {
+    pub window_size: usize,
+    pub markers: Vec<u64>,
+}
+
+// Tracks the longest run of different symbols that ends at the latest one. A marker of any size
+// ends wherever that run is at least as long as it, so one pass finds markers of every size
+struct DistinctRun<T: Symbol> {
+    // One past the position each symbol was last seen at, 0 for never
+    last_seen: T::Table,
+    run_start: u64,
+    position: u64,
+}
+
+impl<T: Symbol> DistinctRun<T> {
+    fn new() -> DistinctRun<T> {
+        DistinctRun {
+            last_seen: T::Table::default(),
+            run_start: 0,
+            position: 0,
+        }
+    }
+
+    fn push(&mut self, symbol: T) -> u64 {
+        self.run_start = self.run_start.max(self.last_seen.get(symbol));
+        self.position += 1;
+        *self.last_seen.get_mut(symbol) = self.position;
+        self.position - self.run_start
+    }
+}
+
+// How many of the symbols up to each one, counting back from it, are all different, for
+// analysing a stream as it goes
+pub fn get_distinct_lengths<T: Symbol>(
+    symbols: impl IntoIterator<Item = T>,
+) -> impl Iterator<Item = u64> {
+    let mut run = DistinctRun::new();
+    symbols.into_iter().map(move |symbol| run.push(symbol))
+}
+
+// Looks for markers of all the sizes in one pass. Looking for the first of each stops as soon
+// as the largest has been found, since any smaller one ends at or before it
+pub fn scan<T: Symbol>(
+    symbols: impl IntoIterator<Item = T>,
+    window_sizes: &[usize],
+    occurrences: Occurrences,
+) -> Vec<MarkerScan> {
+    assert!(
+        !window_sizes.contains(&0),
+        "A marker needs at least one character"
+    );
+    let mut scans: Vec<MarkerScan> = window_sizes
+        .iter()
+        .map(|window_size| MarkerScan {
+            window_size: *window_size,
+            markers: Vec::new(),
+        })
+        .collect();
+    let mut sizes_left = scans.len();
+
+    for (length, position) in get_distinct_lengths(symbols).zip(1..) {
+        if sizes_left == 0 {
+            break;
+        }
+        for scan in &mut scans {
+            if length < scan.window_size as u64
+                || (occurrences == Occurrences::First && !scan.markers.is_empty())
+            {
+                continue;
+            }
+            scan.markers.push(position);
+            if occurrences == Occurrences::First {
+                sizes_left -= 1;
+            }
+        }
+    }
+
+    scans
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stream::{find_marker, MarkerDetector};
+
+    // Every position where the window before it has no repeats, checked the slow way
+    fn get_markers_by_comparing<T: PartialEq>(symbols: &[T], window_size: usize) -> Vec<u64> {
+        (window_size..=symbols.len())
+            .filter(|end| {
+                let window = &symbols[end - window_size..*end];
+                window
+                    .iter()
+                    .enumerate()
+                    .all(|(index, byte)| !window[index + 1..].contains(byte))
+            })
+            .map(|end| end as u64)
+            .collect()
+    }
+
+    #[test]
+    fn it_finds_the_first_marker_of_every_size_in_one_pass() {
+        let scans = scan(
+            "mjqjpqmgbljsphdztnvjfqwrcgsmlb".bytes(),
+            &[14, 4, 30],
+            Occurrences::First,
+        );
+        assert_eq!(
+            scans,
+            [
+                MarkerScan {
+                    window_size: 14,
+                    markers: vec![19]
+                },
+                MarkerScan {
+                    window_size: 4,
+                    markers: vec![7]
+                },
+                MarkerScan {
+                    window_size: 30,
+                    markers: vec![]
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn it_finds_every_marker_like_checking_each_window() {
+        let file_contents = std::fs::read("./input.txt").unwrap();
+        let window_sizes: Vec<usize> = (1..=20).collect();
+        let scans = scan(
+            file_contents.iter().copied(),
+            &window_sizes,
+            Occurrences::Every,
+        );
+
+        for scan in scans {
+            let markers = get_markers_by_comparing(&file_contents, scan.window_size);
+            assert_eq!(scan.markers, markers);
+            assert_eq!(
+                find_marker(&file_contents[..], scan.window_size).unwrap(),
+                markers.first().copied()
+            );
+        }
+    }
+
+    #[test]
+    fn it_reports_the_distinct_length_at_every_offset() {
+        assert_eq!(
+            get_distinct_lengths("abcabba".bytes()).collect::<Vec<u64>>(),
+            [1, 2, 3, 3, 3, 1, 2]
+        );
+    }
+
+    #[test]
+    fn it_finds_the_same_markers_for_any_kind_of_symbol() {
+        let text = "ñaña🦀ñ日本語🦀ab\u{0}cdéfgh";
+        let window_sizes: Vec<usize> = (1..=12).collect();
+        let scans = scan(text.chars(), &window_sizes, Occurrences::Every);
+        let symbols: Vec<char> = text.chars().collect();
+
+        for scan in scans {
+            let mut detector = MarkerDetector::new(scan.window_size);
+            let markers: Vec<u64> = text
+                .chars()
+                .zip(1..)
+                .filter(|(symbol, _)| detector.push(*symbol))
+                .map(|(_, position)| position)
+                .collect();
+            assert_eq!(scan.markers, markers);
+            assert_eq!(
+                scan.markers,
+                get_markers_by_comparing(&symbols, scan.window_size)
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "A marker needs at least one character")]
+    fn it_rejects_markers_without_characters() {
+        scan("abc".bytes(), &[4, 0], Occurrences::First);
+    }
+}
@@ -0,0 +1,148 @@
+use crate::alphabet::{Symbol, SymbolTable};
+use std::io::{self, ErrorKind, Read};
+
+const READ_SIZE: usize = 64 * 1024;
+
+// Keeps the last window_size symbols along with how often each one occurs among them, so every
+// new symbol is a constant amount of work however large the window or the stream
+pub struct MarkerDetector<T: Symbol> {
+    window_size: usize,
+    window: Vec<T>,
+    counts: T::Table,
+    // How many symbols in the window repeat one that's already in it, so 0 means all are different
+    duplicates: usize,
+    // Where the next symbol goes in the window, over the oldest one once it's full
+    next_slot: usize,
+    position: u64,
+}
+
+impl<T: Symbol> MarkerDetector<T> {
+    pub fn new(window_size: usize) -> MarkerDetector<T> {
+        assert!(window_size > 0, "A marker needs at least one character");
+        MarkerDetector {
+            window_size,
+            window: Vec::with_capacity(window_size),
+            counts: T::Table::default(),
+            duplicates: 0,
+            next_slot: 0,
+            position: 0,
+        }
+    }
+
+    // Returns true once the last window_size symbols, up to and including this one, are all
+    // different
+    #[inline]
+    pub fn push(&mut self, symbol: T) -> bool {
+        let slot = self.next_slot;
+        if self.window.len() < self.window_size {
+            self.window.push(symbol);
+        } else {
+            let oldest = std::mem::replace(&mut self.window[slot], symbol);
+            let count = self.counts.get_mut(oldest);
+            *count -= 1;
+            if *count > 0 {
+                self.duplicates -= 1;
+            }
+        }
+        self.next_slot = if slot + 1 == self.window_size {
+            0
+        } else {
+            slot + 1
+        };
+
+        let count = self.counts.get_mut(symbol);
+        *count += 1;
+        if *count > 1 {
+            self.duplicates += 1;
+        }
+        self.position += 1;
+
+        self.duplicates == 0 && self.window.len() == self.window_size
+    }
+
+    // How many symbols have been pushed, which is the marker's position once it's found
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+}
+
+// Reads a chunk at a time and stops as soon as the marker is complete, so nothing after the
+// chunk it ends in is read. Returns how many bytes came up to and including the marker
+pub fn find_marker(mut reader: impl Read, window_size: usize) -> io::Result<Option<u64>> {
+    let mut detector = MarkerDetector::new(window_size);
+    let mut buffer = vec![0; READ_SIZE];
+
+    loop {
+        let length = match reader.read(&mut buffer) {
+            Ok(0) => return Ok(None),
+            Ok(length) => length,
+            Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+            Err(error) => return Err(error),
+        };
+        for byte in &buffer[..length] {
+            if detector.push(*byte) {
+                return Ok(Some(detector.position()));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Hands out at most one byte per read and fails if read past the end of its data, to check
+    // the detector copes with short reads and stops reading at the marker
+    struct TrickleReader<'a> {
+        data: &'a [u8],
+    }
+
+    impl Read for TrickleReader<'_> {
+        fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+            match self.data.split_first() {
+                Some((byte, rest)) => {
+                    buffer[0] = *byte;
+                    self.data = rest;
+                    Ok(1)
+                }
+                None => Err(io::Error::other("read past the end")),
+            }
+        }
+    }
+
+    #[test]
+    fn it_finds_markers_in_any_reader() {
+        assert_eq!(
+            find_marker("mjqjpqmgbljsphdztnvjfqwrcgsmlb".as_bytes(), 4).unwrap(),
+            Some(7)
+        );
+        assert_eq!(
+            find_marker("mjqjpqmgbljsphdztnvjfqwrcgsmlb".as_bytes(), 14).unwrap(),
+            Some(19)
+        );
+        assert_eq!(find_marker("abcabcabc".as_bytes(), 4).unwrap(), None);
+        assert_eq!(find_marker("".as_bytes(), 1).unwrap(), None);
+        assert_eq!(find_marker("aab".as_bytes(), 1).unwrap(), Some(1));
+    }
+
+    #[test]
+    fn it_stops_reading_at_the_marker() {
+        let reader = TrickleReader {
+            data: b"bvwbjplbgvbhsrlpgdmjqwftvncz",
+        };
+        assert_eq!(find_marker(reader, 4).unwrap(), Some(5));
+    }
+
+    #[test]
+    fn it_never_finds_a_window_larger_than_the_alphabet() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        assert_eq!(find_marker(&bytes[..], 256).unwrap(), Some(256));
+        assert_eq!(find_marker(&bytes[..], 257).unwrap(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "A marker needs at least one character")]
+    fn it_rejects_markers_without_characters() {
+        MarkerDetector::<u8>::new(0);
+    }
+}
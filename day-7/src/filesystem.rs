@@ -0,0 +1,398 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+// Entries are kept in one list and refer to each other by their place in it
+pub type EntryId = usize;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Kind {
+    // Children by name, so they list in order
+    Directory(BTreeMap<String, EntryId>),
+    File(u64),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Entry {
+    pub name: String,
+    pub parent: Option<EntryId>,
+    pub kind: Kind,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct FileSystem {
+    entries: Vec<Entry>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParseTranscriptError {
+    UnknownCommand { line_number: usize, command: String },
+    OutputWithoutLs { line_number: usize },
+    InvalidListing { line_number: usize, listing: String },
+    AboveRoot { line_number: usize },
+    NotADirectory { line_number: usize, name: String },
+    ListedDifferently { line_number: usize, name: String },
+}
+
+impl fmt::Display for ParseTranscriptError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseTranscriptError::UnknownCommand {
+                line_number,
+                command,
+            } => write!(f, "Line {}: unknown command {:?}", line_number, command),
+            ParseTranscriptError::OutputWithoutLs { line_number } => {
+                write!(f, "Line {}: output without an ls before it", line_number)
+            }
+            ParseTranscriptError::InvalidListing {
+                line_number,
+                listing,
+            } => write!(
+                f,
+                "Line {}: expected \"dir <name>\" or \"<size> <name>\" but found {:?}",
+                line_number, listing
+            ),
+            ParseTranscriptError::AboveRoot { line_number } => {
+                write!(f, "Line {}: can't go above the root directory", line_number)
+            }
+            ParseTranscriptError::NotADirectory { line_number, name } => {
+                write!(f, "Line {}: {} isn't a directory", line_number, name)
+            }
+            ParseTranscriptError::ListedDifferently { line_number, name } => write!(
+                f,
+                "Line {}: {} was listed before as something else",
+                line_number, name
+            ),
+        }
+    }
+}
+
+pub const ROOT: EntryId = 0;
+
+impl FileSystem {
+    fn new() -> FileSystem {
+        FileSystem {
+            entries: vec![Entry {
+                name: String::new(),
+                parent: None,
+                kind: Kind::Directory(BTreeMap::new()),
+            }],
+        }
+    }
+
+    pub fn entry(&self, id: EntryId) -> &Entry {
+        &self.entries[id]
+    }
+
+    pub fn is_directory(&self, id: EntryId) -> bool {
+        matches!(self.entries[id].kind, Kind::Directory(_))
+    }
+
+    // Files have no children
+    pub fn children(&self, id: EntryId) -> impl Iterator<Item = EntryId> + '_ {
+        let children = match &self.entries[id].kind {
+            Kind::Directory(children) => Some(children.values().copied()),
+            Kind::File(_) => None,
+        };
+        children.into_iter().flatten()
+    }
+
+    pub fn child(&self, id: EntryId, name: &str) -> Option<EntryId> {
+        match &self.entries[id].kind {
+            Kind::Directory(children) => children.get(name).copied(),
+            Kind::File(_) => None,
+        }
+    }
+
+    // Paths are absolute, like /a/e, and can end with a slash
+    pub fn lookup(&self, path: &str) -> Option<EntryId> {
+        path.strip_prefix('/')?
+            .split('/')
+            .filter(|name| !name.is_empty())
+            .try_fold(ROOT, |id, name| self.child(id, name))
+    }
+
+    pub fn path(&self, id: EntryId) -> String {
+        let mut names: Vec<&str> = Vec::new();
+        let mut current = id;
+        while let Some(parent) = self.entries[current].parent {
+            names.push(&self.entries[current].name);
+            current = parent;
+        }
+        names.reverse();
+        format!("/{}", names.join("/"))
+    }
+
+    // The size of a file, or of everything under a directory
+    pub fn size(&self, id: EntryId) -> u64 {
+        match &self.entries[id].kind {
+            Kind::File(size) => *size,
+            Kind::Directory(children) => children.values().map(|child| self.size(*child)).sum(),
+        }
+    }
+
+    // The size of every directory, root first, worked out in one pass over the tree
+    pub fn get_directory_sizes(&self) -> Vec<(EntryId, u64)> {
+        // Children always come after their parents, so going backwards sees them first
+        let mut sizes: Vec<u64> = vec![0; self.entries.len()];
+        for (id, entry) in self.entries.iter().enumerate().rev() {
+            if let Kind::File(size) = entry.kind {
+                sizes[id] = size;
+            }
+            if let Some(parent) = entry.parent {
+                sizes[parent] += sizes[id];
+            }
+        }
+
+        (0..self.entries.len())
+            .filter(|id| self.is_directory(*id))
+            .map(|id| (id, sizes[id]))
+            .collect()
+    }
+
+    // A directory that's already there is reused, anything else listed twice has to match
+    fn add(
+        &mut self,
+        parent: EntryId,
+        name: &str,
+        kind: Kind,
+        line_number: usize,
+    ) -> Result<EntryId, ParseTranscriptError> {
+        if let Some(existing) = self.child(parent, name) {
+            return match (&self.entries[existing].kind, &kind) {
+                (Kind::Directory(_), Kind::Directory(_)) => Ok(existing),
+                (Kind::File(size), Kind::File(new_size)) if size == new_size => Ok(existing),
+                _ => Err(ParseTranscriptError::ListedDifferently {
+                    line_number,
+                    name: name.to_string(),
+                }),
+            };
+        }
+
+        let id = self.entries.len();
+        self.entries.push(Entry {
+            name: name.to_string(),
+            parent: Some(parent),
+            kind,
+        });
+        if let Kind::Directory(children) = &mut self.entries[parent].kind {
+            children.insert(name.to_string(), id);
+        }
+        Ok(id)
+    }
+}
+
+// Draws the tree the way the puzzle does, one entry per line
+impl fmt::Display for FileSystem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut pending: Vec<(EntryId, usize)> = vec![(ROOT, 0)];
+        while let Some((id, depth)) = pending.pop() {
+            let entry = &self.entries[id];
+            let name = if id == ROOT { "/" } else { &entry.name };
+            match entry.kind {
+                Kind::Directory(_) => writeln!(f, "{}- {} (dir)", "  ".repeat(depth), name)?,
+                Kind::File(size) => {
+                    writeln!(f, "{}- {} (file, size={})", "  ".repeat(depth), name, size)?
+                }
+            }
+            let children: Vec<EntryId> = self.children(id).collect();
+            pending.extend(children.into_iter().rev().map(|child| (child, depth + 1)));
+        }
+        Ok(())
+    }
+}
+
+// Builds the tree from the commands and what they printed. Going into a directory that wasn't
+// listed is taken to mean it's there, so a transcript that skips some ls output still works
+pub fn parse_transcript(transcript: &str) -> Result<FileSystem, ParseTranscriptError> {
+    let mut file_system = FileSystem::new();
+    let mut current = ROOT;
+    let mut is_listing = false;
+
+    for (index, line) in transcript.lines().enumerate() {
+        let line_number = index + 1;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        if let Some(command) = line.strip_prefix("$ ") {
+            is_listing = false;
+            match command.split_whitespace().collect::<Vec<&str>>()[..] {
+                ["cd", "/"] => current = ROOT,
+                ["cd", ".."] => {
+                    current = file_system.entries[current]
+                        .parent
+                        .ok_or(ParseTranscriptError::AboveRoot { line_number })?;
+                }
+                ["cd", name] => {
+                    if let Some(Kind::File(_)) = file_system
+                        .child(current, name)
+                        .map(|child| &file_system.entries[child].kind)
+                    {
+                        return Err(ParseTranscriptError::NotADirectory {
+                            line_number,
+                            name: name.to_string(),
+                        });
+                    }
+                    current = file_system.add(
+                        current,
+                        name,
+                        Kind::Directory(BTreeMap::new()),
+                        line_number,
+                    )?;
+                }
+                ["ls"] => is_listing = true,
+                _ => {
+                    return Err(ParseTranscriptError::UnknownCommand {
+                        line_number,
+                        command: command.to_string(),
+                    })
+                }
+            }
+            continue;
+        }
+
+        if !is_listing {
+            return Err(ParseTranscriptError::OutputWithoutLs { line_number });
+        }
+        let (description, name) =
+            line.split_once(' ')
+                .ok_or_else(|| ParseTranscriptError::InvalidListing {
+                    line_number,
+                    listing: line.to_string(),
+                })?;
+        let kind = match description {
+            "dir" => Kind::Directory(BTreeMap::new()),
+            size => Kind::File(
+                size.parse()
+                    .map_err(|_| ParseTranscriptError::InvalidListing {
+                        line_number,
+                        listing: line.to_string(),
+                    })?,
+            ),
+        };
+        file_system.add(current, name, kind, line_number)?;
+    }
+
+    Ok(file_system)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn parse_test_file() -> FileSystem {
+        let file_contents =
+            fs::read_to_string("./test.txt").expect("Should have been able to read the file");
+        parse_transcript(&file_contents).unwrap()
+    }
+
+    #[test]
+    fn it_draws_the_test_tree_like_the_puzzle() {
+        assert_eq!(
+            parse_test_file().to_string(),
+            "- / (dir)
+  - a (dir)
+    - e (dir)
+      - i (file, size=584)
+    - f (file, size=29116)
+    - g (file, size=2557)
+    - h.lst (file, size=62596)
+  - b.txt (file, size=14848514)
+  - c.dat (file, size=8504156)
+  - d (dir)
+    - d.ext (file, size=5626152)
+    - d.log (file, size=8033020)
+    - j (file, size=4060174)
+    - k (file, size=7214296)
+"
+        );
+    }
+
+    #[test]
+    fn it_looks_up_paths_and_sizes() {
+        let file_system = parse_test_file();
+
+        let e = file_system.lookup("/a/e/").unwrap();
+        assert_eq!(file_system.path(e), "/a/e");
+        assert_eq!(file_system.size(e), 584);
+        assert_eq!(file_system.size(file_system.lookup("/a").unwrap()), 94853);
+        assert_eq!(
+            file_system.size(file_system.lookup("/d").unwrap()),
+            24933642
+        );
+        assert_eq!(file_system.size(ROOT), 48381165);
+        assert_eq!(file_system.lookup("/"), Some(ROOT));
+        assert_eq!(file_system.path(ROOT), "/");
+
+        let i = file_system.lookup("/a/e/i").unwrap();
+        assert!(!file_system.is_directory(i));
+        assert_eq!(file_system.entry(i).kind, Kind::File(584));
+        assert_eq!(file_system.children(i).count(), 0);
+        assert_eq!(file_system.lookup("/a/e/i/x"), None);
+        assert_eq!(file_system.lookup("/z"), None);
+        assert_eq!(file_system.lookup("a"), None);
+
+        let names: Vec<&str> = file_system
+            .children(file_system.lookup("/a").unwrap())
+            .map(|child| file_system.entry(child).name.as_str())
+            .collect();
+        assert_eq!(names, ["e", "f", "g", "h.lst"]);
+    }
+
+    #[test]
+    fn it_works_out_every_directory_size_at_once() {
+        let file_system = parse_test_file();
+
+        for (id, size) in file_system.get_directory_sizes() {
+            assert!(file_system.is_directory(id));
+            assert_eq!(size, file_system.size(id));
+        }
+        assert_eq!(file_system.get_directory_sizes().len(), 4);
+    }
+
+    #[test]
+    fn it_reports_transcripts_that_do_not_make_sense() {
+        assert_eq!(
+            parse_transcript("$ cd /\n$ cd .."),
+            Err(ParseTranscriptError::AboveRoot { line_number: 2 })
+        );
+        assert_eq!(
+            parse_transcript("$ rm -rf /"),
+            Err(ParseTranscriptError::UnknownCommand {
+                line_number: 1,
+                command: "rm -rf /".to_string()
+            })
+        );
+        assert_eq!(
+            parse_transcript("$ cd /\n12 a"),
+            Err(ParseTranscriptError::OutputWithoutLs { line_number: 2 })
+        );
+        assert_eq!(
+            parse_transcript("$ ls\nbig a").unwrap_err().to_string(),
+            "Line 2: expected \"dir <name>\" or \"<size> <name>\" but found \"big a\""
+        );
+        assert_eq!(
+            parse_transcript("$ ls\n12 a\n$ cd a"),
+            Err(ParseTranscriptError::NotADirectory {
+                line_number: 3,
+                name: "a".to_string()
+            })
+        );
+        assert_eq!(
+            parse_transcript("$ ls\n12 a\n$ ls\ndir a"),
+            Err(ParseTranscriptError::ListedDifferently {
+                line_number: 4,
+                name: "a".to_string()
+            })
+        );
+
+        // Listing a directory again is fine, and so is going into one that wasn't listed
+        let file_system = parse_transcript("$ ls\n12 a\n$ ls\n12 a\n$ cd b\n$ ls\n3 c").unwrap();
+        assert_eq!(file_system.size(ROOT), 15);
+        assert_eq!(
+            file_system.path(file_system.lookup("/b/c").unwrap()),
+            "/b/c"
+        );
+    }
+}
@@ -1,74 +1,80 @@
+mod filesystem;
+
+use filesystem::{parse_transcript, FileSystem, Kind, ROOT};
+use std::env;
 use std::fs;
 
-fn get_totals(file_path: &str) -> Vec<u32> {
-    let mut totals: Vec<u32> = Vec::new();
-    let mut stack: Vec<u32> = Vec::new();
+const DISK_SPACE: u64 = 70000000;
+const SPACE_NEEDED_FOR_UPDATE: u64 = 30000000;
 
+fn get_file_system(file_path: &str) -> FileSystem {
     let file_contents =
         fs::read_to_string(file_path).expect("Should have been able to read the file");
 
-    for line in file_contents.lines() {
-        if line.starts_with("$ cd") {
-            let cd_arg = line.split(' ').skip(2).next().unwrap();
-            if cd_arg == ".." {
-                // Exit current directory.  Save its total size.
-                totals.push(stack.pop().unwrap());
-            } else {
-                // Entering a new directory
-                // Initialize the size (so far) to 0.
-                stack.push(0);
-            }
-        } else {
-            // Try to parse a number at the start of the line (i.e. a file size)
-            let file_name_start = line.split(' ').next().unwrap();
-            if let Ok(file_size) = file_name_start.parse::<u32>() {
-                // Add the size of this file to all ancestor directories
-                for dir in stack.iter_mut() {
-                    *dir += file_size;
-                }
-            }
-        }
-    }
-
-    // Pop any directories still on the stack
-    while let Some(v) = stack.pop() {
-        totals.push(v);
-    }
-
-    return totals;
+    parse_transcript(&file_contents).unwrap_or_else(|error| panic!("{}", error))
 }
 
-fn get_sum_of_totals(file_path: &str) -> u32 {
-    let totals = get_totals(file_path);
-
-    totals
+fn get_sum_of_totals(file_path: &str) -> u64 {
+    get_file_system(file_path)
+        .get_directory_sizes()
         .into_iter()
+        .map(|(_, total)| total)
         .filter(|total| total < &100000)
-        .sum::<u32>()
+        .sum::<u64>()
 }
 
-fn get_size_of_smallest_directory_large_enough_to_be_removed(file_path: &str) -> u32 {
-    let mut totals = get_totals(file_path);
+fn get_size_of_smallest_directory_large_enough_to_be_removed(file_path: &str) -> u64 {
+    let file_system = get_file_system(file_path);
+    let totals = file_system.get_directory_sizes();
 
-    let total_amount_of_unused_space = totals.last().unwrap();
+    let remaining_unused_disk_space = DISK_SPACE - file_system.size(ROOT);
 
-    let remaining_unused_disk_space = 70000000 - total_amount_of_unused_space;
+    let space_required_to_free_up = SPACE_NEEDED_FOR_UPDATE - remaining_unused_disk_space;
 
-    let space_required_to_free_up = 30000000 - remaining_unused_disk_space;
-
-    totals.sort();
-
-    let smallest_directories_large_enough_to_be_removed = totals
+    totals
         .into_iter()
+        .map(|(_, total)| total)
         .filter(|total| total > &space_required_to_free_up)
-        .collect::<Vec<u32>>();
+        .min()
+        .unwrap()
+}
 
-    return smallest_directories_large_enough_to_be_removed[0];
+fn lookup(file_system: &FileSystem, path: &str) -> usize {
+    file_system
+        .lookup(path)
+        .unwrap_or_else(|| panic!("There's nothing at {}", path))
 }
 
 fn main() {
-    get_sum_of_totals("./test.txt");
-    get_size_of_smallest_directory_large_enough_to_be_removed("./input.txt");
+    let arguments: Vec<String> = env::args().skip(1).collect();
+
+    match arguments.iter().map(String::as_str).collect::<Vec<&str>>()[..] {
+        // cargo run -- tree ./test.txt
+        ["tree", file_path] => print!("{}", get_file_system(file_path)),
+        // cargo run -- ls ./test.txt /a
+        ["ls", file_path, path] => {
+            let file_system = get_file_system(file_path);
+            for child in file_system.children(lookup(&file_system, path)) {
+                match file_system.entry(child).kind {
+                    Kind::Directory(_) => println!("dir {}", file_system.entry(child).name),
+                    Kind::File(size) => println!("{} {}", size, file_system.entry(child).name),
+                }
+            }
+        }
+        // cargo run -- du ./test.txt /a
+        ["du", file_path, path] => {
+            let file_system = get_file_system(file_path);
+            let id = lookup(&file_system, path);
+            println!("{} {}", file_system.size(id), file_system.path(id));
+        }
+        _ => {
+            println!("Part 1: {}", get_sum_of_totals("./input.txt"));
+            println!(
+                "Part 2: {}",
+                get_size_of_smallest_directory_large_enough_to_be_removed("./input.txt")
+            );
+        }
+    }
 }
 
 #[cfg(test)]